@@ -0,0 +1,96 @@
+//! Compares the collecting `lexer` with the streaming `Lexer` on a generated multi-megabyte
+//! program, reporting the best wall time and the peak heap use of each.
+//!
+//! Run with `cargo bench --bench lexer`.
+
+use calru::lexer::{lexer, Lexer};
+use calru::parser::Parser;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Tracks the bytes currently allocated and the most ever allocated at once.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const BLOCKS: usize = 15_000;
+const RUNS: usize = 3;
+
+fn generate_program() -> String {
+    let mut source = String::new();
+    for i in 0..BLOCKS {
+        source.push_str(&format!(
+            "/// Counter number {i}.\n\
+             let mut counter_{i} :int := 0x{i:X} + 1_000;\n\
+             let label_{i} := \"counter\\t{i} of the generated program\";\n\
+             let scale_{i} :float := 1.5e3 * {i}.25; // trailing comment\n\
+             let limit_{i} := {i} % 7 + 10;\n\
+             /* a block comment /* nested */ before the loop */\n\
+             loop {{\n    counter_{i} += 1;\n    if (counter_{i} == limit_{i}) then\n        break;\n    end\n}}\n"
+        ));
+    }
+    source
+}
+
+/// Runs `work` `RUNS` times and returns its best time and its peak heap use in bytes.
+fn measure<T>(mut work: impl FnMut() -> T) -> (Duration, usize) {
+    let mut best = Duration::MAX;
+    let mut peak = 0;
+    for _ in 0..RUNS {
+        let baseline = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(baseline, Ordering::Relaxed);
+        let start = Instant::now();
+        black_box(work());
+        best = best.min(start.elapsed());
+        peak = peak.max(PEAK.load(Ordering::Relaxed) - baseline);
+    }
+    (best, peak)
+}
+
+fn report(name: &str, bytes: usize, (time, peak): (Duration, usize)) {
+    let megabytes = bytes as f64 / (1024.0 * 1024.0);
+    println!(
+        "{:<28} {:>10.2?} {:>10.1} MB/s {:>12.1} MB peak heap",
+        name,
+        time,
+        megabytes / time.as_secs_f64(),
+        peak as f64 / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    let source = generate_program();
+    println!("input: {:.1} MB, {} lines", source.len() as f64 / (1024.0 * 1024.0), source.lines().count());
+
+    report("lexer() into Vec<Token>", source.len(), measure(|| lexer(&source).unwrap().len()));
+    report("Lexer iterator", source.len(), measure(|| Lexer::new(&source).map(Result::unwrap).count()));
+    report(
+        "parse Vec<Token>",
+        source.len(),
+        measure(|| Parser::new(lexer(&source).unwrap()).parse_program().unwrap().0.len()),
+    );
+    report(
+        "parse streamed Lexer",
+        source.len(),
+        measure(|| Parser::from_lexer(Lexer::new(&source)).parse_program().unwrap().0.len()),
+    );
+}
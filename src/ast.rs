@@ -1,21 +1,103 @@
+use crate::format::FormatSpec;
+use crate::models::Position;
+use crate::symbol_table::{Mutability, SymbolType, SymbolValue};
 use std::fmt;
 #[derive(Debug, Clone, PartialEq)]
 pub enum ASTNode {
     Int(i64),
     Float(f64),
     Boolean(bool),
+    String(String),
     Identifier(String),
     List(Vec<AST>),
+    Map(Vec<(AST, AST)>),
+    Tuple(Vec<AST>),
+    TupleIndex {
+        tuple: Box<AST>,
+        index: usize,
+    },
+    None,
+    IsNone {
+        value: Box<AST>,
+    },
+    UnwrapOr {
+        value: Box<AST>,
+        default: Box<AST>,
+    },
+    TryPop {
+        list: Box<AST>,
+    },
+    Get {
+        collection: Box<AST>,
+        index: Box<AST>,
+    },
+    EnumDecl {
+        name: String,
+        variants: Vec<(String, Vec<SymbolType>)>,
+    },
+    /// `enum_type` is the full `SymbolType::Enum`, so the variant's type is known without the declaration.
+    EnumVariant {
+        enum_type: SymbolType,
+        variant: String,
+        values: Vec<AST>,
+    },
+    /// Arm bodies can refer to pattern bindings, so their common type is recorded while parsing.
+    Match {
+        scrutinee: Box<AST>,
+        arms: Vec<MatchArm>,
+        result_type: SymbolType,
+    },
+    /// The parser has already evaluated the constant. A constant declared in a block leaves
+    /// the parser's symbol table with the block, so the interpreter binds the value again.
+    ConstDecl {
+        name: String,
+        symbol_type: SymbolType,
+        value: SymbolValue,
+    },
+    StructDecl {
+        name: String,
+        fields: Vec<(String, SymbolType)>,
+    },
+    StructLiteral {
+        name: String,
+        fields: Vec<(String, AST)>,
+    },
+    FieldAccess {
+        object: Box<AST>,
+        field: String,
+    },
+    FieldAssignment {
+        variable: String,
+        fields: Vec<String>,
+        expression: Box<AST>,
+    },
+    Destructure {
+        variables: Vec<(String, SymbolType)>,
+        mutability: Mutability,
+        expression: Box<AST>,
+    },
     BinaryOperation {
         operator: String,
         left: Box<AST>,
         right: Box<AST>,
     },
+    Let {
+        variable: String,
+        symbol_type: SymbolType,
+        mutability: Mutability,
+        expression: Box<AST>,
+    },
     Assignment {
         variable: String,
         expression: Box<AST>,
     },
-    Print(Box<AST>),
+    Print {
+        arguments: Vec<AST>,
+        /// The `sep:` argument, joining the others in place of `PRINT_SEPARATOR`.
+        separator: Option<Box<AST>>,
+        stream: OutputStream,
+        newline: bool,
+    },
     If {
         condition: Box<AST>,
         then_branch: Box<AST>,
@@ -35,20 +117,169 @@ pub enum ASTNode {
     Loop {
         body: Box<AST>,
     },
+    /// Each body statement keeps its position, which a caught runtime failure reports.
+    Try {
+        body: Vec<(AST, Position)>,
+        variable: String,
+        handler: Vec<AST>,
+    },
+    Throw {
+        value: Box<AST>,
+        position: Position,
+    },
+    /// `start` and `end` are the positions of the builtin's name and its closing ')'.
+    Assert {
+        condition: Box<AST>,
+        start: Position,
+        end: Position,
+    },
+    AssertEq {
+        left: Box<AST>,
+        right: Box<AST>,
+        start: Position,
+        end: Position,
+    },
+    Test {
+        name: String,
+        body: Vec<AST>,
+    },
     Break,
     Len {
         list: Box<AST>,
     },
+    Index {
+        collection: Box<AST>,
+        index: Box<AST>,
+    },
+    IndexAssignment {
+        variable: String,
+        index: Box<AST>,
+        expression: Box<AST>,
+    },
+    Keys {
+        map: Box<AST>,
+    },
+    Values {
+        map: Box<AST>,
+    },
+    ContainsKey {
+        map: Box<AST>,
+        key: Box<AST>,
+    },
+    Remove {
+        map: Box<AST>,
+        key: Box<AST>,
+    },
+    Stdin(InputKind),
+    ReadFile {
+        path: Box<AST>,
+    },
+    ReadLines {
+        path: Box<AST>,
+    },
+    WriteFile {
+        path: Box<AST>,
+        contents: Box<AST>,
+    },
+    Format(Vec<FormatSegment>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatSegment {
+    Literal(String),
+    Placeholder {
+        expression: Box<AST>,
+        spec: FormatSpec,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: AST,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `Shape.Rect(w, h)`; a binding named `_` is not bound.
+    Variant {
+        variant: String,
+        bindings: Vec<(String, SymbolType)>,
+    },
+    Wildcard,
+}
+
+impl Pattern {
+    pub fn matches(&self, variant: &str) -> bool {
+        match self {
+            Pattern::Variant { variant: name, .. } => name == variant,
+            Pattern::Wildcard => true,
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Variant { variant, bindings } if bindings.is_empty() => write!(f, "{}", variant),
+            Pattern::Variant { variant, bindings } => {
+                let names: Vec<&str> = bindings.iter().map(|(name, _)| name.as_str()).collect();
+                write!(f, "{}({})", variant, names.join(", "))
+            }
+            Pattern::Wildcard => write!(f, "_"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputKind {
+    Line,
+    Int,
+    Float,
+}
+
+impl InputKind {
+    pub fn symbol_type(&self) -> SymbolType {
+        match self {
+            InputKind::Line => SymbolType::String,
+            InputKind::Int => SymbolType::Int,
+            InputKind::Float => SymbolType::Float,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct AST {
     pub node: ASTNode,
+    /// The `///` comment written above a declaration, one line per source line.
+    pub doc: Option<String>,
 }
 
 impl AST {
     pub fn new(node: ASTNode) -> AST {
-        AST { node }
+        AST { node, doc: None }
+    }
+
+    pub fn with_doc(mut self, doc: String) -> AST {
+        self.doc = Some(doc);
+        self
+    }
+}
+
+impl AST {
+    /// Whether this is a `[]` or `{}` literal that takes its type from `expected`.
+    pub fn is_empty_collection_of(&self, expected: &SymbolType) -> bool {
+        match (&self.node, expected) {
+            (ASTNode::List(elements), SymbolType::List(_)) => elements.is_empty(),
+            (ASTNode::Map(entries), SymbolType::Map(_, _)) => entries.is_empty(),
+            _ => false,
+        }
     }
 }
 
@@ -64,12 +295,23 @@ impl fmt::Display for ASTNode {
             ASTNode::Int(value) => write!(f, "Int({})", value),
             ASTNode::Float(value) => write!(f, "Float({})", value),
             ASTNode::Boolean(value) => write!(f, "Boolean({})",value),
+            ASTNode::String(value) => write!(f, "String({:?})", value),
             ASTNode::Identifier(id) => write!(f, "Identifier({})", id),
+            ASTNode::Let { variable, symbol_type, mutability, expression } => {
+                write!(f, "Let({:?} {}: {:?} = {})", mutability, variable, symbol_type, expression)
+            }
             ASTNode::Assignment { variable, expression } => {
                 write!(f, "Assignment({} = {})", variable, expression)
             }
-            ASTNode::Print(expression) => {
-                write!(f, "Print({})", expression)
+            ASTNode::Print { arguments, separator, stream, newline } => {
+                let arguments_str = arguments.iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match separator {
+                    Some(separator) => write!(f, "Print({:?}, newline: {}, sep: {}, [{}])", stream, newline, separator, arguments_str),
+                    None => write!(f, "Print({:?}, newline: {}, [{}])", stream, newline, arguments_str),
+                }
             }
             ASTNode::If { condition, then_branch, else_branch } => {
                 write!(f, "If({} then {} else {})", condition, then_branch, 
@@ -87,6 +329,33 @@ impl fmt::Display for ASTNode {
             ASTNode::Loop { body } => {
                 write!(f, "Loop({})", body)
             }
+            ASTNode::Try { body, variable, handler } => {
+                let body_str = body.iter()
+                    .map(|(statement, _)| format!("{}", statement))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let handler_str = handler.iter()
+                    .map(|statement| format!("{}", statement))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Try([{}] catch {} [{}])", body_str, variable, handler_str)
+            }
+            ASTNode::Throw { value, .. } => {
+                write!(f, "Throw({})", value)
+            }
+            ASTNode::Assert { condition, .. } => {
+                write!(f, "Assert({})", condition)
+            }
+            ASTNode::AssertEq { left, right, .. } => {
+                write!(f, "AssertEq({}, {})", left, right)
+            }
+            ASTNode::Test { name, body } => {
+                let body_str = body.iter()
+                    .map(|statement| format!("{}", statement))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Test({:?} [{}])", name, body_str)
+            }
             ASTNode::Break => {
                 write!(f, "Break")
             }
@@ -103,6 +372,116 @@ impl fmt::Display for ASTNode {
             ASTNode::Len { list } => {
                 write!(f, "Len({}.len())", list)
             }
+            ASTNode::Map(entries) => {
+                let entries_str = entries.iter()
+                    .map(|(k, v)| format!("{}: {}", k, v))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Map({{{}}})", entries_str)
+            }
+            ASTNode::Tuple(elements) => {
+                let elements_str = elements.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Tuple(({}))", elements_str)
+            }
+            ASTNode::TupleIndex { tuple, index } => {
+                write!(f, "TupleIndex({}.{})", tuple, index)
+            }
+            ASTNode::None => write!(f, "None"),
+            ASTNode::IsNone { value } => write!(f, "IsNone({})", value),
+            ASTNode::UnwrapOr { value, default } => write!(f, "UnwrapOr({}, {})", value, default),
+            ASTNode::TryPop { list } => write!(f, "TryPop({})", list),
+            ASTNode::Get { collection, index } => write!(f, "Get({}[{}])", collection, index),
+            ASTNode::EnumDecl { name, variants } => {
+                let variants_str = variants.iter()
+                    .map(|(variant, payload)| format!("{}{:?}", variant, payload))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "EnumDecl({} {{ {} }})", name, variants_str)
+            }
+            ASTNode::EnumVariant { variant, values, .. } => {
+                let values_str = values.iter()
+                    .map(|value| format!("{}", value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "EnumVariant({}({}))", variant, values_str)
+            }
+            ASTNode::Match { scrutinee, arms, .. } => {
+                let arms_str = arms.iter()
+                    .map(|arm| format!("{} => {}", arm.pattern, arm.body))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Match({} {{ {} }})", scrutinee, arms_str)
+            }
+            ASTNode::ConstDecl { name, value, .. } => {
+                write!(f, "ConstDecl({} = {:?})", name, value)
+            }
+            ASTNode::StructDecl { name, fields } => {
+                let fields_str = fields.iter()
+                    .map(|(field, field_type)| format!("{}: {:?}", field, field_type))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "StructDecl({} {{ {} }})", name, fields_str)
+            }
+            ASTNode::StructLiteral { name, fields } => {
+                let fields_str = fields.iter()
+                    .map(|(field, value)| format!("{}: {}", field, value))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "StructLiteral({} {{ {} }})", name, fields_str)
+            }
+            ASTNode::FieldAccess { object, field } => {
+                write!(f, "FieldAccess({}.{})", object, field)
+            }
+            ASTNode::FieldAssignment { variable, fields, expression } => {
+                write!(f, "FieldAssignment({}.{} = {})", variable, fields.join("."), expression)
+            }
+            ASTNode::Destructure { variables, expression, .. } => {
+                let names = variables.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+                write!(f, "Destructure(({}) = {})", names.join(", "), expression)
+            }
+            ASTNode::Index { collection, index } => {
+                write!(f, "Index({}[{}])", collection, index)
+            }
+            ASTNode::IndexAssignment { variable, index, expression } => {
+                write!(f, "IndexAssignment({}[{}] = {})", variable, index, expression)
+            }
+            ASTNode::Keys { map } => {
+                write!(f, "Keys({}.keys())", map)
+            }
+            ASTNode::Values { map } => {
+                write!(f, "Values({}.values())", map)
+            }
+            ASTNode::ContainsKey { map, key } => {
+                write!(f, "ContainsKey({}.contains_key({}))", map, key)
+            }
+            ASTNode::Remove { map, key } => {
+                write!(f, "Remove({}.remove({}))", map, key)
+            }
+            ASTNode::Stdin(kind) => {
+                write!(f, "Stdin({:?})", kind)
+            }
+            ASTNode::ReadFile { path } => {
+                write!(f, "ReadFile({})", path)
+            }
+            ASTNode::ReadLines { path } => {
+                write!(f, "ReadLines({})", path)
+            }
+            ASTNode::WriteFile { path, contents } => {
+                write!(f, "WriteFile({}, {})", path, contents)
+            }
+            ASTNode::Format(segments) => {
+                let segments_str = segments.iter()
+                    .map(|segment| match segment {
+                        FormatSegment::Literal(text) => format!("{:?}", text),
+                        FormatSegment::Placeholder { expression, .. } => expression.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Format([{}])", segments_str)
+            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,377 @@
+//! A lossless concrete syntax tree. Every byte of the source belongs to exactly one token or
+//! one piece of trivia (whitespace, comments, text the lexer rejected), so printing the tree
+//! gives back the source byte for byte. Formatters and editor tooling read exact text and
+//! positions from it. Its nodes are the parser's productions, and `Cst::to_ast` gives the
+//! `AST` the same parse built.
+
+use crate::ast::{ASTNode, AST};
+use crate::lexer::{Lexer, RawToken};
+use crate::models::{Position, Token, TokenType};
+use crate::parser::{Event, Parser};
+use crate::symbol_table::SymbolTable;
+use crate::token_spec::{BLANKS, BLOCK_COMMENT, TOKEN_CLASSES};
+use regex::Regex;
+use std::fmt;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TriviaKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    /// Text the lexer reported as invalid, or left unread after a fatal error.
+    Skipped,
+}
+
+/// Source text between two tokens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia<'src> {
+    pub kind: TriviaKind,
+    pub text: &'src str,
+    /// Byte offset of `text` in the source.
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstToken<'src> {
+    pub raw: RawToken<'src>,
+    /// The trivia between the previous token and this one.
+    pub leading_trivia: Vec<Trivia<'src>>,
+}
+
+impl<'src> CstToken<'src> {
+    /// The token's span along with its leading trivia.
+    pub fn full_span(&self) -> Range<usize> {
+        let start = self.leading_trivia.first().map_or(self.raw.offset, |trivia| trivia.offset);
+        start..self.raw.span().end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeKind {
+    Program,
+    // Statements, each with its `;` if it has one
+    /// A `let`, destructuring or not.
+    Let,
+    Const,
+    StructDecl,
+    EnumDecl,
+    /// `:=`, a compound assignment, or an assignment to an element or a field.
+    Assignment,
+    /// `stdout`, `print` or `stderr`.
+    Print,
+    If,
+    Loop,
+    Break,
+    Try,
+    Throw,
+    /// `assert` or `assert_eq`.
+    Assert,
+    Test,
+    WriteFile,
+    // Parts of statements
+    /// The braces of a loop, try, catch or test and the statements in them.
+    Block,
+    /// The statement of an if's `then` or `else`.
+    Branch,
+    /// A `catch` clause, from the keyword to the end of its block.
+    Catch,
+    /// The type after a declared name's `:`.
+    Type,
+    // Expressions. A literal or a name is a token of its own, not a node.
+    Binary,
+    /// A parenthesized expression.
+    Paren,
+    Tuple,
+    List,
+    Map,
+    StructLiteral,
+    /// An enum variant, with its values if any.
+    EnumValue,
+    Match,
+    MatchArm,
+    /// An element of a list or map.
+    Index,
+    /// A struct field or tuple element.
+    Field,
+    /// A builtin or method call, as an expression or a statement.
+    Call,
+}
+
+impl NodeKind {
+    /// The kind of node a parsed statement or expression has; `None` for a literal or a name.
+    pub(crate) fn of(node: &ASTNode) -> Option<NodeKind> {
+        let kind = match node {
+            ASTNode::Int(_) | ASTNode::Float(_) | ASTNode::Boolean(_) | ASTNode::String(_)
+            | ASTNode::Format(_) | ASTNode::None | ASTNode::Identifier(_) => return None,
+            ASTNode::Let { .. } | ASTNode::Destructure { .. } => NodeKind::Let,
+            ASTNode::ConstDecl { .. } => NodeKind::Const,
+            ASTNode::StructDecl { .. } => NodeKind::StructDecl,
+            ASTNode::EnumDecl { .. } => NodeKind::EnumDecl,
+            ASTNode::Assignment { .. } | ASTNode::IndexAssignment { .. } | ASTNode::FieldAssignment { .. } => {
+                NodeKind::Assignment
+            }
+            ASTNode::Print { .. } => NodeKind::Print,
+            ASTNode::If { .. } => NodeKind::If,
+            ASTNode::Loop { .. } => NodeKind::Loop,
+            ASTNode::Break => NodeKind::Break,
+            ASTNode::Try { .. } => NodeKind::Try,
+            ASTNode::Throw { .. } => NodeKind::Throw,
+            ASTNode::Assert { .. } | ASTNode::AssertEq { .. } => NodeKind::Assert,
+            ASTNode::Test { .. } => NodeKind::Test,
+            ASTNode::WriteFile { .. } => NodeKind::WriteFile,
+            ASTNode::BinaryOperation { .. } => NodeKind::Binary,
+            ASTNode::Tuple(_) => NodeKind::Tuple,
+            ASTNode::List(_) => NodeKind::List,
+            ASTNode::Map(_) => NodeKind::Map,
+            ASTNode::StructLiteral { .. } => NodeKind::StructLiteral,
+            ASTNode::EnumVariant { .. } => NodeKind::EnumValue,
+            ASTNode::Match { .. } => NodeKind::Match,
+            ASTNode::Index { .. } => NodeKind::Index,
+            ASTNode::FieldAccess { .. } | ASTNode::TupleIndex { .. } => NodeKind::Field,
+            ASTNode::IsNone { .. } | ASTNode::UnwrapOr { .. } | ASTNode::TryPop { .. } | ASTNode::Get { .. }
+            | ASTNode::Fetch { .. } | ASTNode::Push { .. } | ASTNode::Pop { .. } | ASTNode::Len { .. }
+            | ASTNode::Keys { .. } | ASTNode::Values { .. } | ASTNode::ContainsKey { .. } | ASTNode::Remove { .. }
+            | ASTNode::Stdin(_) | ASTNode::ReadFile { .. } | ASTNode::ReadLines { .. } => NodeKind::Call,
+        };
+        Some(kind)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CstElement<'src> {
+    Node(CstNode<'src>),
+    Token(CstToken<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CstNode<'src> {
+    pub kind: NodeKind,
+    pub children: Vec<CstElement<'src>>,
+}
+
+impl<'src> CstNode<'src> {
+    /// The node's tokens in source order.
+    pub fn tokens(&self) -> Vec<&CstToken<'src>> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a CstToken<'src>>) {
+        for child in &self.children {
+            match child {
+                CstElement::Node(node) => node.collect_tokens(tokens),
+                CstElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    /// The child nodes, skipping tokens.
+    pub fn nodes(&self) -> impl Iterator<Item = &CstNode<'src>> {
+        self.children.iter().filter_map(|child| match child {
+            CstElement::Node(node) => Some(node),
+            CstElement::Token(_) => None,
+        })
+    }
+
+    /// The bytes the node covers, including the leading trivia of its first token.
+    pub fn span(&self) -> Range<usize> {
+        let tokens = self.tokens();
+        match (tokens.first(), tokens.last()) {
+            (Some(first), Some(last)) => first.full_span().start..last.raw.span().end,
+            _ => 0..0,
+        }
+    }
+}
+
+impl fmt::Display for CstNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for token in self.tokens() {
+            for trivia in &token.leading_trivia {
+                f.write_str(trivia.text)?;
+            }
+            f.write_str(token.raw.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// The concrete syntax tree of one source file. Its root is the `Program` node, ending
+/// with the `EOF` token, whose leading trivia holds whatever follows the last token.
+#[derive(Debug, Clone)]
+pub struct Cst<'src> {
+    pub root: CstNode<'src>,
+    /// Lexer and parser errors. A tree is built regardless, with the statement the parser
+    /// stopped in, and everything after it, left as loose tokens under the root.
+    pub errors: Vec<String>,
+    /// What the parse built the tree from produced, unless it failed.
+    parsed: Option<(Vec<AST>, SymbolTable)>,
+}
+
+impl<'src> Cst<'src> {
+    pub fn parse(source: &'src str) -> Cst<'src> {
+        let mut errors = Vec::new();
+        let mut raw_tokens = Vec::new();
+        for token in Lexer::new(source) {
+            match token {
+                Ok(token) => raw_tokens.push(token),
+                Err(e) => errors.push(e),
+            }
+        }
+        if raw_tokens.last().is_none_or(|token| token.token_type != TokenType::EOF) {
+            // A fatal lexer error ends the tokens early; the rest of the source is skipped.
+            raw_tokens.push(RawToken { token_type: TokenType::EOF, text: "", position: end_position(source), offset: source.len() });
+        }
+
+        let mut parser = Parser::new(raw_tokens.iter().map(|token| Token::from(*token)).collect()).with_source(source);
+        let parsed = parser.parse_program().map_err(|e| errors.push(e)).ok();
+        let ranges = node_ranges(&parser.events);
+
+        let mut tokens = Vec::with_capacity(raw_tokens.len());
+        let mut end = 0;
+        for raw in raw_tokens {
+            let leading_trivia = split_trivia(&source[end..raw.offset], end);
+            end = raw.span().end;
+            tokens.push(CstToken { raw, leading_trivia });
+        }
+
+        Cst { root: build_tree(tokens, ranges), errors, parsed }
+    }
+
+    /// The `AST` that `Parser::parse_program` produces for the source, from the parse that
+    /// built the tree.
+    pub fn to_ast(&self) -> Result<(Vec<AST>, SymbolTable), String> {
+        self.parsed.clone().ok_or_else(|| self.errors.join("\n"))
+    }
+
+    /// The token whose text contains byte `offset`, for editor features like hover.
+    pub fn token_at(&self, offset: usize) -> Option<&CstToken<'src>> {
+        self.root.tokens().into_iter().find(|token| token.raw.span().contains(&offset))
+    }
+}
+
+impl fmt::Display for Cst<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.fmt(f)
+    }
+}
+
+fn end_position(source: &str) -> Position {
+    let last_line = source.rsplit('\n').next().unwrap_or("");
+    Position { line: source.matches('\n').count() + 1, column: last_line.chars().count() + 1 }
+}
+
+/// The token range of each node the parser's events describe, in the order the nodes start.
+fn node_ranges(events: &[Event]) -> Vec<(NodeKind, Range<usize>)> {
+    let mut ranges = Vec::new();
+    let mut open = Vec::new();
+    for event in events {
+        match *event {
+            Event::Start { kind, token } => {
+                open.push(ranges.len());
+                ranges.push((kind, token..token));
+            }
+            Event::Finish { token } => {
+                let index = open.pop().expect("every finished node was started");
+                ranges[index].1.end = token;
+            }
+        }
+    }
+    ranges
+}
+
+/// Nests `tokens` into a `Program` node holding a node for each range. A range that
+/// crosses the boundary of one it starts inside is dropped.
+fn build_tree<'src>(tokens: Vec<CstToken<'src>>, mut ranges: Vec<(NodeKind, Range<usize>)>) -> CstNode<'src> {
+    ranges.sort_by_key(|(_, range)| (range.start, std::cmp::Reverse(range.end)));
+    let mut ranges = ranges.into_iter().peekable();
+    let mut stack = vec![(CstNode { kind: NodeKind::Program, children: Vec::new() }, tokens.len())];
+
+    for (index, token) in tokens.into_iter().enumerate() {
+        while stack.len() > 1 && stack.last().is_some_and(|(_, end)| *end <= index) {
+            close_node(&mut stack);
+        }
+        while let Some((kind, range)) = ranges.next_if(|(_, range)| range.start == index) {
+            let enclosing_end = stack.last().map_or(usize::MAX, |(_, end)| *end);
+            if range.end <= enclosing_end {
+                stack.push((CstNode { kind, children: Vec::new() }, range.end));
+            }
+        }
+        stack.last_mut().unwrap().0.children.push(CstElement::Token(token));
+    }
+    while stack.len() > 1 {
+        close_node(&mut stack);
+    }
+    stack.pop().unwrap().0
+}
+
+fn close_node(stack: &mut Vec<(CstNode, usize)>) {
+    let (node, _) = stack.pop().unwrap();
+    stack.last_mut().unwrap().0.children.push(CstElement::Node(node));
+}
+
+/// The token classes the lexer skips, anchored to match at the start of a text.
+fn comment_patterns() -> &'static [Regex] {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        TOKEN_CLASSES
+            .iter()
+            .filter(|class| class.token_type.is_none())
+            .map(|class| Regex::new(&format!("^(?:{})", class.pattern)).expect("token patterns are valid"))
+            .collect()
+    })
+}
+
+/// Splits the text between two tokens, which starts at byte `offset`, into trivia.
+fn split_trivia(text: &str, offset: usize) -> Vec<Trivia<'_>> {
+    let (open, close) = BLOCK_COMMENT;
+    let mut trivia: Vec<Trivia> = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let rest = &text[start..];
+        let comment = comment_patterns().iter().find_map(|pattern| pattern.find(rest));
+        let (kind, len) = if let Some(comment) = comment.filter(|comment| !comment.is_empty()) {
+            (TriviaKind::LineComment, comment.end())
+        } else if rest.starts_with(open) {
+            (TriviaKind::BlockComment, block_comment_len(rest, open, close))
+        } else if BLANKS.contains(&rest.as_bytes()[0]) {
+            (TriviaKind::Whitespace, rest.bytes().position(|byte| !BLANKS.contains(&byte)).unwrap_or(rest.len()))
+        } else {
+            (TriviaKind::Skipped, rest.chars().next().map_or(1, char::len_utf8))
+        };
+        match trivia.last_mut() {
+            // Runs of skipped characters form one piece.
+            Some(last) if kind == TriviaKind::Skipped && last.kind == TriviaKind::Skipped => {
+                last.text = &text[last.offset - offset..start + len];
+            }
+            _ => trivia.push(Trivia { kind, text: &rest[..len], offset: offset + start }),
+        }
+        start += len;
+    }
+    trivia
+}
+
+/// The length of the (possibly nested) block comment `text` starts with, or all of `text`
+/// if it is unterminated.
+fn block_comment_len(text: &str, open: &str, close: &str) -> usize {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < text.len() {
+        let rest = &text[index..];
+        if rest.starts_with(open) {
+            depth += 1;
+            index += open.len();
+        } else if rest.starts_with(close) {
+            depth -= 1;
+            index += close.len();
+            if depth == 0 {
+                return index;
+            }
+        } else {
+            index += rest.chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    text.len()
+}
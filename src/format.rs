@@ -0,0 +1,206 @@
+use crate::symbol_table::SymbolValue;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// The part of a placeholder after the `:`, e.g. `>8.2` in `{avg:>8.2}`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FormatSpec {
+    pub align: Option<Align>,
+    pub width: Option<usize>,
+    pub precision: Option<usize>,
+}
+
+/// A piece of a string literal before its placeholders are parsed as expressions.
+/// `offset` and `length` are counted in characters of the literal's text, including the braces.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawSegment {
+    Literal(String),
+    Placeholder {
+        source: String,
+        spec: FormatSpec,
+        offset: usize,
+        length: usize,
+    },
+}
+
+/// Splits `text` into literal runs and `{expression:spec}` placeholders. `{{` and `}}` are
+/// literal braces. Errors carry the character offset they refer to.
+pub fn split_placeholders(text: &str) -> Result<Vec<RawSegment>, (usize, String)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                literal.push('{');
+                i += 2;
+            }
+            '}' if chars.get(i + 1) == Some(&'}') => {
+                literal.push('}');
+                i += 2;
+            }
+            '}' => return Err((i, "Unmatched '}' in string literal, use '}}' for a literal brace".to_string())),
+            '{' => {
+                let close = find_closing_brace(&chars, i)
+                    .ok_or_else(|| (i, "Unclosed '{' in string literal, use '{{' for a literal brace".to_string()))?;
+                let inner: Vec<char> = chars[i + 1..close].to_vec();
+                let (source, spec) = match find_spec_separator(&inner) {
+                    Some(colon) => {
+                        let spec_text: String = inner[colon + 1..].iter().collect();
+                        let spec = parse_spec(&spec_text).map_err(|e| (i, e))?;
+                        (inner[..colon].iter().collect::<String>(), spec)
+                    }
+                    None => (inner.iter().collect::<String>(), FormatSpec::default()),
+                };
+                if source.trim().is_empty() {
+                    return Err((i, "Empty placeholder '{}' in string literal".to_string()));
+                }
+
+                if !literal.is_empty() {
+                    segments.push(RawSegment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(RawSegment::Placeholder {
+                    source,
+                    spec,
+                    offset: i,
+                    length: close - i + 1,
+                });
+                i = close + 1;
+            }
+            ch => {
+                literal.push(ch);
+                i += 1;
+            }
+        }
+    }
+
+    if !literal.is_empty() || segments.is_empty() {
+        segments.push(RawSegment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn find_closing_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in chars.iter().enumerate().skip(open) {
+        match ch {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_spec_separator(inner: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    for (i, ch) in inner.iter().enumerate() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ':' if depth == 0 => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_spec(text: &str) -> Result<FormatSpec, String> {
+    let mut chars = text.chars().peekable();
+    let mut spec = FormatSpec {
+        align: match chars.peek() {
+            Some('<') => Some(Align::Left),
+            Some('>') => Some(Align::Right),
+            Some('^') => Some(Align::Center),
+            _ => None,
+        },
+        ..FormatSpec::default()
+    };
+    if spec.align.is_some() {
+        chars.next();
+    }
+
+    let width: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit())).collect();
+    if !width.is_empty() {
+        spec.width = Some(width.parse().map_err(|_| format!("Invalid width '{}' in format spec", width))?);
+    }
+
+    if chars.next_if_eq(&'.').is_some() {
+        let precision: String = std::iter::from_fn(|| chars.next_if(|c| c.is_ascii_digit())).collect();
+        if precision.is_empty() {
+            return Err(format!("Expected precision after '.' in format spec ':{}'", text));
+        }
+        spec.precision = Some(precision.parse().map_err(|_| format!("Invalid precision '{}' in format spec", precision))?);
+    }
+
+    if chars.peek().is_some() {
+        return Err(format!("Invalid format spec ':{}'", text));
+    }
+    Ok(spec)
+}
+
+/// Formats `value` according to `spec`. Lists apply the spec to each element.
+pub fn render(value: &SymbolValue, spec: &FormatSpec) -> String {
+    match value {
+        SymbolValue::List(values) => {
+            let values_str: Vec<String> = values.iter().map(|v| render(v, spec)).collect();
+            format!("[{}]", values_str.join(", "))
+        }
+        SymbolValue::Tuple(values) => {
+            let values_str: Vec<String> = values.iter().map(|v| render(v, spec)).collect();
+            format!("({})", values_str.join(", "))
+        }
+        SymbolValue::Struct { name, fields } => {
+            let fields_str: Vec<String> = fields.iter()
+                .map(|(field, v)| format!("{}: {}", field, render(v, spec)))
+                .collect();
+            format!("{} {{ {} }}", name, fields_str.join(", "))
+        }
+        SymbolValue::Enum { name, variant, values } if !values.is_empty() => {
+            let values_str: Vec<String> = values.iter().map(|v| render(v, spec)).collect();
+            format!("{}.{}({})", name, variant, values_str.join(", "))
+        }
+        SymbolValue::Map(entries) => {
+            let entries_str: Vec<String> = entries.iter()
+                .map(|(k, v)| format!("{}: {}", k, render(v, spec)))
+                .collect();
+            format!("{{{}}}", entries_str.join(", "))
+        }
+        SymbolValue::Float(v) => {
+            let text = match spec.precision {
+                Some(precision) => format!("{:.*}", precision, v),
+                None => v.to_string(),
+            };
+            pad(text, spec, Align::Right)
+        }
+        SymbolValue::Int(_) => pad(value.to_string(), spec, Align::Right),
+        _ => pad(value.to_string(), spec, Align::Left),
+    }
+}
+
+fn pad(text: String, spec: &FormatSpec, default_align: Align) -> String {
+    let width = spec.width.unwrap_or(0);
+    let len = text.chars().count();
+    if len >= width {
+        return text;
+    }
+
+    let fill = width - len;
+    match spec.align.unwrap_or(default_align) {
+        Align::Left => format!("{}{}", text, " ".repeat(fill)),
+        Align::Right => format!("{}{}", " ".repeat(fill), text),
+        Align::Center => format!("{}{}{}", " ".repeat(fill / 2), text, " ".repeat(fill - fill / 2)),
+    }
+}
@@ -1,32 +1,130 @@
-use crate::symbol_table::{SymbolTable, SymbolType, SymbolValue};
-use crate::ast::{AST, ASTNode};
+use crate::symbol_table::{Mutability, Symbol, SymbolTable, SymbolType, SymbolValue};
+use crate::ast::{AST, ASTNode, FormatSegment, InputKind, OutputStream, Pattern};
+use crate::format;
 use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// The result of one `test "name" { ... }` block in test mode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestOutcome {
+    pub name: String,
+    pub result: Result<(), String>,
+}
+
+/// Placed between the arguments of a multi-argument `stdout`, `print` or `stderr` without `sep:`.
+pub const PRINT_SEPARATOR: &str = ", ";
 
 pub struct Interpreter {
     symbol_table: SymbolTable,
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    error_output: Box<dyn Write>,
+    file_root: Option<PathBuf>,
+    /// The error raised by the `throw` currently unwinding, so `catch` receives it unchanged.
+    thrown: Option<SymbolValue>,
 }
 
 impl Interpreter {
     pub fn new(symbol_table: SymbolTable) -> Self {
-        Interpreter { symbol_table }
+        Interpreter {
+            symbol_table,
+            input: Box::new(io::BufReader::new(io::stdin())),
+            output: Box::new(io::stdout()),
+            error_output: Box::new(io::stderr()),
+            file_root: None,
+            thrown: None,
+        }
+    }
+
+    /// Reads `stdin_*` builtins from `input` instead of the process's standard input.
+    pub fn with_input(mut self, input: impl BufRead + 'static) -> Self {
+        self.input = Box::new(input);
+        self
+    }
+
+    /// Sends everything the program prints to `output` instead of the process's standard output.
+    pub fn with_output(mut self, output: impl Write + 'static) -> Self {
+        self.output = Box::new(output);
+        self
+    }
+
+    /// Sends `stderr(...)` output to `error_output` instead of the process's standard error.
+    pub fn with_error_output(mut self, error_output: impl Write + 'static) -> Self {
+        self.error_output = Box::new(error_output);
+        self
+    }
+
+    /// Enables the file builtins for paths inside `root`. File I/O is disabled until this is called.
+    pub fn with_file_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.file_root = Some(root.into());
+        self
+    }
+
+    pub fn symbol_table(&self) -> &SymbolTable {
+        &self.symbol_table
     }
 
     pub fn run(&mut self, statements: Vec<AST>) -> Result<(), String> {
+        self.thrown = None;
         for statement in statements {
             self.execute_statement(&statement)?;
         }
         Ok(())
     }
 
+    /// Runs the program's top-level declarations (`let`, `const`, `struct` and `enum`), then
+    /// each test block against a copy of the resulting state. Other top-level statements, like
+    /// output, file writes or assignments, are skipped. Writes a pass/fail line per block and
+    /// a summary to the output.
+    pub fn run_tests(&mut self, statements: Vec<AST>) -> Result<Vec<TestOutcome>, String> {
+        self.thrown = None;
+        let (tests, rest): (Vec<AST>, Vec<AST>) = statements.into_iter()
+            .partition(|statement| matches!(statement.node, ASTNode::Test { .. }));
+        let setup = rest.into_iter().filter(|statement| matches!(
+            statement.node,
+            ASTNode::Let { .. } | ASTNode::Destructure { .. } | ASTNode::ConstDecl { .. }
+                | ASTNode::StructDecl { .. } | ASTNode::EnumDecl { .. }
+        ));
+        self.run(setup.collect())?;
+
+        let mut outcomes = Vec::new();
+        for test in tests {
+            let ASTNode::Test { name, body } = test.node else { continue };
+            let snapshot = self.symbol_table.clone();
+            self.symbol_table.enter_scope();
+            let result = body.iter().try_for_each(|statement| self.execute_statement(statement));
+            self.symbol_table = snapshot;
+            self.thrown = None;
+
+            match &result {
+                Ok(_) => self.write(OutputStream::Stdout, &format!("test {} ... ok\n", name))?,
+                Err(e) => self.write(OutputStream::Stdout, &format!("test {} ... FAILED: {}\n", name, e))?,
+            }
+            outcomes.push(TestOutcome { name, result });
+        }
+
+        let failed = outcomes.iter().filter(|outcome| outcome.result.is_err()).count();
+        self.write(OutputStream::Stdout, &format!("{} passed; {} failed\n", outcomes.len() - failed, failed))?;
+        Ok(outcomes)
+    }
+
     fn execute_statement(&mut self, ast: &AST) -> Result<(), String> {
         match &ast.node {
+            ASTNode::Let { variable, symbol_type, mutability, expression } => {
+                let value = self.evaluate_expression(expression)?;
+                let symbol = Symbol { symbol_type: symbol_type.clone(), value, mutability: *mutability, declared_at: None };
+                self.symbol_table.define(variable.clone(), symbol);
+            },
             ASTNode::Assignment { variable, expression } => {
                 let value = self.evaluate_expression(expression)?;
                 let symbol_type = self.symbol_table.lookup(variable)
                     .ok_or_else(|| format!("Variable '{}' not found.", variable))?
                     .symbol_type.clone();
     
-                if self.infer_type(&AST::new(ASTNode::Assignment { variable: variable.clone(), expression: expression.clone() }))? != symbol_type {
+                if !expression.is_empty_collection_of(&symbol_type)
+                    && !symbol_type.accepts(&self.infer_type(&AST::new(ASTNode::Assignment { variable: variable.clone(), expression: expression.clone() }))?) {
                     return Err(format!(
                         "Type mismatch: cannot assign value of type {:?} to variable of type {:?}.",
                         self.infer_type(&AST::new(ASTNode::Assignment { variable: variable.clone(), expression: expression.clone() }))?,
@@ -36,20 +134,36 @@ impl Interpreter {
     
                 self.symbol_table.update(variable.clone(), value)?;
             },
-            ASTNode::Print(expression) => {
-                let value = self.evaluate_expression(expression)?;
-                println!("{:?}", value); // Use `{:?}` for debug output
+            ASTNode::Print { arguments, separator, stream, newline } => {
+                let mut values = Vec::new();
+                for argument in arguments {
+                    values.push(self.evaluate_expression(argument)?.to_string());
+                }
+                let separator = match separator {
+                    Some(separator) => self.evaluate_expression(separator)?.to_string(),
+                    None => PRINT_SEPARATOR.to_string(),
+                };
+                let mut text = values.join(&separator);
+                if *newline {
+                    text.push('\n');
+                }
+                self.write(*stream, &text)?;
             },
             ASTNode::Boolean(b) => {
                 // Handle Boolean nodes if needed
-                println!("Boolean value: {}", b);
+                self.write(OutputStream::Stdout, &format!("Boolean value: {}\n", b))?;
             },
             ASTNode::If { condition, then_branch, else_branch } => {
                 let condition_value = self.evaluate_expression(condition)?;
-                if let SymbolValue::Boolean(true) = condition_value {
-                    self.execute_statement(then_branch)?;
-                } else if let Some(else_branch) = else_branch {
-                    self.execute_statement(else_branch)?;
+                let branch = match condition_value {
+                    SymbolValue::Boolean(true) => Some(then_branch),
+                    _ => else_branch.as_ref(),
+                };
+                if let Some(branch) = branch {
+                    self.symbol_table.enter_scope();
+                    let result = self.execute_statement(branch);
+                    self.symbol_table.exit_scope();
+                    result?;
                 }
             },
             ASTNode::List(elements) => {
@@ -98,22 +212,139 @@ impl Interpreter {
             ASTNode::Len { list } => {
                 let list_value = self.evaluate_expression(list)?;
                 if let SymbolValue::List(elements) = list_value {
-                    println!("{}", elements.len());
+                    self.write(OutputStream::Stdout, &format!("{}\n", elements.len()))?;
                 } else {
                     return Err("Len operation can only be performed on lists.".to_string());
                 }
             },
+            ASTNode::StructDecl { .. } | ASTNode::EnumDecl { .. } => {},
+            ASTNode::ConstDecl { name, symbol_type, value } => {
+                // Redefined on each pass through a block, like a `let`.
+                let symbol = Symbol { symbol_type: symbol_type.clone(), value: value.clone(), mutability: Mutability::Constant, declared_at: None };
+                self.symbol_table.define(name.clone(), symbol);
+            },
+            ASTNode::FieldAssignment { variable, fields, expression } => {
+                let value = self.evaluate_expression(expression)?;
+                self.symbol_table.set_field(variable, fields, value)?;
+            },
+            ASTNode::Destructure { variables, mutability, expression } => {
+                let values = match self.evaluate_expression(expression)? {
+                    SymbolValue::Tuple(values) if values.len() == variables.len() => values,
+                    other => return Err(format!("Cannot destructure {} into {} variables.", other, variables.len())),
+                };
+                for ((variable, symbol_type), value) in variables.iter().zip(values) {
+                    let symbol = Symbol { symbol_type: symbol_type.clone(), value, mutability: *mutability, declared_at: None };
+                    self.symbol_table.define(variable.clone(), symbol);
+                }
+            },
+            ASTNode::IndexAssignment { variable, index, expression } => {
+                let index = self.evaluate_expression(index)?;
+                let value = self.evaluate_expression(expression)?;
+                self.symbol_table.set_index(variable, index, value)?;
+            },
+            ASTNode::Remove { .. } => {
+                self.evaluate_expression(ast)?;
+            },
+            ASTNode::WriteFile { path, contents } => {
+                let path = self.evaluate_path(path)?;
+                let contents = match self.evaluate_expression(contents)? {
+                    SymbolValue::String(contents) => contents,
+                    other => return Err(format!("write_file expects string contents, found {:?}.", other)),
+                };
+                fs::write(&path, contents)
+                    .map_err(|e| format!("Failed to write file '{}': {}", path.display(), e))?;
+            },
+            ASTNode::Try { body, variable, handler } => {
+                self.symbol_table.enter_scope();
+                let mut failure = None;
+                for (statement, position) in body {
+                    match self.execute_statement(statement) {
+                        Ok(_) => {},
+                        Err(e) if e == "break" => {
+                            self.symbol_table.exit_scope();
+                            return Err(e);
+                        },
+                        Err(e) => {
+                            failure = Some(self.thrown.take().unwrap_or(SymbolValue::Error { message: e, position: *position }));
+                            break;
+                        },
+                    }
+                }
+                self.symbol_table.exit_scope();
+
+                if let Some(error) = failure {
+                    self.symbol_table.enter_scope();
+                    self.symbol_table.insert(variable.clone(), SymbolType::Error, error)?;
+                    let result = handler.iter().try_for_each(|statement| self.execute_statement(statement));
+                    self.symbol_table.exit_scope();
+                    result?;
+                }
+            },
+            ASTNode::Throw { value, position } => {
+                let error = match self.evaluate_expression(value)? {
+                    SymbolValue::String(message) => SymbolValue::Error { message, position: *position },
+                    error @ SymbolValue::Error { .. } => error,
+                    other => return Err(format!("throw expects a string or an error, found {}.", other)),
+                };
+                let message = error.to_string();
+                self.thrown = Some(error);
+                return Err(message);
+            },
+            ASTNode::Assert { condition, start, end } => {
+                if self.evaluate_expression(condition)? != SymbolValue::Boolean(true) {
+                    return Err(format!("Assertion failed at position {:?} to {:?}.", start, end));
+                }
+            },
+            ASTNode::AssertEq { left, right, start, end } => {
+                let left = self.evaluate_expression(left)?;
+                let right = self.evaluate_expression(right)?;
+                if left != right {
+                    return Err(format!(
+                        "Assertion failed: left {} != right {} at position {:?} to {:?}.",
+                        left, right, start, end
+                    ));
+                }
+            },
+            // Test blocks only run in test mode.
+            ASTNode::Test { .. } => {},
             ASTNode::Break => return Err("break".to_string()),
             _ => return Err(format!("Unsupported statement {:?}", ast.node)),
         }
         Ok(())
     }
 
-    fn evaluate_expression(&self, expression: &AST) -> Result<SymbolValue, String> {
+    fn evaluate_expression(&mut self, expression: &AST) -> Result<SymbolValue, String> {
         match &expression.node {
             ASTNode::Int(value) => Ok(SymbolValue::Int(*value)),
             ASTNode::Float(value) => Ok(SymbolValue::Float(*value)),
             ASTNode::Boolean(value) => Ok(SymbolValue::Boolean(*value)),
+            ASTNode::String(value) => Ok(SymbolValue::String(value.clone())),
+            ASTNode::Stdin(kind) => self.read_input(*kind),
+            ASTNode::ReadFile { path } => {
+                let path = self.evaluate_path(path)?;
+                fs::read_to_string(&path)
+                    .map(SymbolValue::String)
+                    .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))
+            },
+            ASTNode::ReadLines { path } => {
+                let path = self.evaluate_path(path)?;
+                let contents = fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read file '{}': {}", path.display(), e))?;
+                Ok(SymbolValue::List(contents.lines().map(|line| SymbolValue::String(line.to_string())).collect()))
+            },
+            ASTNode::Format(segments) => {
+                let mut text = String::new();
+                for segment in segments {
+                    match segment {
+                        FormatSegment::Literal(literal) => text.push_str(literal),
+                        FormatSegment::Placeholder { expression, spec } => {
+                            let value = self.evaluate_expression(expression)?;
+                            text.push_str(&format::render(&value, spec));
+                        }
+                    }
+                }
+                Ok(SymbolValue::String(text))
+            },
             ASTNode::Identifier(id) => {
                 let symbol = self.symbol_table.lookup(id)
                     .ok_or_else(|| format!("Variable '{}' not found.", id))?;
@@ -153,7 +384,8 @@ impl Interpreter {
                             "+" => SymbolValue::Int(left_val + right_val),
                             "-" => SymbolValue::Int(left_val - right_val),
                             "*" => SymbolValue::Int(left_val * right_val),
-                            "/" => SymbolValue::Int(left_val / right_val),
+                            "/" => SymbolValue::Int(left_val.checked_div(right_val).ok_or("Division by zero.")?),
+                            "%" => SymbolValue::Int(left_val.checked_rem(right_val).ok_or("Division by zero.")?),
                             ">" => SymbolValue::Boolean(left_val > right_val),
                             "<" => SymbolValue::Boolean(left_val < right_val),
                             ">=" => SymbolValue::Boolean(left_val >= right_val),
@@ -170,6 +402,7 @@ impl Interpreter {
                             "-" => SymbolValue::Float(left_val - right_val),
                             "*" => SymbolValue::Float(left_val * right_val),
                             "/" => SymbolValue::Float(left_val / right_val),
+                            "%" => SymbolValue::Float(left_val % right_val),
                             ">" => SymbolValue::Boolean(left_val > right_val),
                             "<" => SymbolValue::Boolean(left_val < right_val),
                             ">=" => SymbolValue::Boolean(left_val >= right_val),
@@ -186,6 +419,7 @@ impl Interpreter {
                             "-" => SymbolValue::Float((left_val as f64) - right_val),
                             "*" => SymbolValue::Float((left_val as f64) * right_val),
                             "/" => SymbolValue::Float((left_val as f64) / right_val),
+                            "%" => SymbolValue::Float((left_val as f64) % right_val),
                             ">" => SymbolValue::Boolean((left_val as f64) > right_val),
                             "<" => SymbolValue::Boolean((left_val as f64) < right_val),
                             ">=" => SymbolValue::Boolean((left_val as f64) >= right_val),
@@ -202,6 +436,7 @@ impl Interpreter {
                             "-" => SymbolValue::Float(left_val - (right_val as f64)),
                             "*" => SymbolValue::Float(left_val * (right_val as f64)),
                             "/" => SymbolValue::Float(left_val / (right_val as f64)),
+                            "%" => SymbolValue::Float(left_val % (right_val as f64)),
                             ">" => SymbolValue::Boolean(left_val > (right_val as f64)),
                             "<" => SymbolValue::Boolean(left_val < (right_val as f64)),
                             ">=" => SymbolValue::Boolean(left_val >= (right_val as f64)),
@@ -222,6 +457,18 @@ impl Interpreter {
                         };
                         Ok(result)
                     },
+                    (SymbolValue::String(left_val), SymbolValue::String(right_val)) => {
+                        let result = match operator.as_str() {
+                            "==" => SymbolValue::Boolean(left_val == right_val),
+                            "!=" => SymbolValue::Boolean(left_val != right_val),
+                            _ => return Err(format!("Unsupported operator '{}' in binary operation.", operator)),
+                        };
+                        Ok(result)
+                    },
+                    (SymbolValue::List(mut left_val), SymbolValue::List(right_val)) if operator == "+" => {
+                        left_val.extend(right_val);
+                        Ok(SymbolValue::List(left_val))
+                    },
                     _ => Err("Type mismatch in binary operation.".to_string()),
                 }
             },
@@ -259,15 +506,202 @@ impl Interpreter {
                     Err("Len operation can only be performed on lists.".to_string())
                 }
             },
+            ASTNode::Map(entries) => {
+                let mut map = SymbolValue::Map(Vec::new());
+                for (key, value) in entries {
+                    let key = self.evaluate_expression(key)?;
+                    let value = self.evaluate_expression(value)?;
+                    map.set_index(key, value)?;
+                }
+                Ok(map)
+            },
+            ASTNode::Tuple(elements) => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.evaluate_expression(element)?);
+                }
+                Ok(SymbolValue::Tuple(values))
+            },
+            ASTNode::StructLiteral { name, fields } => {
+                let mut values = Vec::new();
+                for (field, value) in fields {
+                    values.push((field.clone(), self.evaluate_expression(value)?));
+                }
+                Ok(SymbolValue::Struct { name: name.clone(), fields: values })
+            },
+            ASTNode::FieldAccess { object, field } => self.evaluate_expression(object)?.field(field),
+            ASTNode::None => Ok(SymbolValue::None),
+            ASTNode::IsNone { value } => Ok(SymbolValue::Boolean(self.evaluate_expression(value)? == SymbolValue::None)),
+            ASTNode::UnwrapOr { value, default } => match self.evaluate_expression(value)? {
+                SymbolValue::None => self.evaluate_expression(default),
+                value => Ok(value),
+            },
+            ASTNode::TryPop { list } => {
+                let list_name = match &list.node {
+                    ASTNode::Identifier(name) => name,
+                    _ => return Err("try_pop requires a list identifier.".to_string()),
+                };
+                match self.evaluate_expression(list)? {
+                    SymbolValue::List(elements) if elements.is_empty() => Ok(SymbolValue::None),
+                    _ => self.symbol_table.pop(list_name),
+                }
+            },
+            ASTNode::Get { collection, index } => {
+                let collection = self.evaluate_expression(collection)?;
+                let index = self.evaluate_expression(index)?;
+                Ok(collection.get(&index))
+            },
+            ASTNode::EnumVariant { enum_type, variant, values } => {
+                let name = match enum_type {
+                    SymbolType::Enum { name, .. } => name.clone(),
+                    other => return Err(format!("Expected an enum type, found {:?}.", other)),
+                };
+                let mut payload = Vec::new();
+                for value in values {
+                    payload.push(self.evaluate_expression(value)?);
+                }
+                Ok(SymbolValue::Enum { name, variant: variant.clone(), values: payload })
+            },
+            ASTNode::Match { scrutinee, arms, .. } => {
+                let (variant, values) = match self.evaluate_expression(scrutinee)? {
+                    SymbolValue::Enum { variant, values, .. } => (variant, values),
+                    other => return Err(format!("Match can only be performed on enums, found {}.", other)),
+                };
+                let arm = arms.iter()
+                    .find(|arm| arm.pattern.matches(&variant))
+                    .ok_or_else(|| format!("No match arm for variant '{}'.", variant))?;
+
+                self.symbol_table.enter_scope();
+                if let Pattern::Variant { bindings, .. } = &arm.pattern {
+                    for ((binding, binding_type), value) in bindings.iter().zip(values) {
+                        if binding != "_" {
+                            self.symbol_table.insert(binding.clone(), binding_type.clone(), value)?;
+                        }
+                    }
+                }
+                let result = self.evaluate_expression(&arm.body);
+                self.symbol_table.exit_scope();
+                result
+            },
+            ASTNode::TupleIndex { tuple, index } => {
+                match self.evaluate_expression(tuple)? {
+                    SymbolValue::Tuple(values) => values.get(*index).cloned()
+                        .ok_or_else(|| format!("Tuple index {} out of bounds.", index)),
+                    _ => Err("Tuple indexing can only be performed on tuples.".to_string()),
+                }
+            },
+            ASTNode::Index { collection, index } => {
+                let collection_value = self.evaluate_expression(collection)?;
+                let index_value = self.evaluate_expression(index)?;
+                collection_value.index(&index_value)
+            },
+            ASTNode::Keys { map } | ASTNode::Values { map } => {
+                let keys = matches!(expression.node, ASTNode::Keys { .. });
+                match self.evaluate_expression(map)? {
+                    SymbolValue::Map(entries) => Ok(SymbolValue::List(
+                        entries.into_iter().map(|(k, v)| if keys { k } else { v }).collect()
+                    )),
+                    _ => Err("keys and values can only be performed on maps.".to_string()),
+                }
+            },
+            ASTNode::ContainsKey { map, key } => {
+                let key_value = self.evaluate_expression(key)?;
+                self.evaluate_expression(map)?.contains_key(&key_value).map(SymbolValue::Boolean)
+            },
+            ASTNode::Remove { map, key } => {
+                let map_name = if let ASTNode::Identifier(name) = &map.node {
+                    name
+                } else {
+                    return Err("Remove operation requires a map identifier.".to_string());
+                };
+
+                let key_value = self.evaluate_expression(key)?;
+                self.symbol_table.remove(map_name, &key_value)
+            },
             _ => Err(format!("Cannot evaluate expression node {:?}", expression.node)),
         }
     }
 
+    fn write(&mut self, stream: OutputStream, text: &str) -> Result<(), String> {
+        let output = match stream {
+            OutputStream::Stdout => &mut self.output,
+            OutputStream::Stderr => &mut self.error_output,
+        };
+        output.write_all(text.as_bytes())
+            .and_then(|_| output.flush())
+            .map_err(|e| format!("Failed to write output: {}", e))
+    }
+
+    fn evaluate_path(&mut self, path: &AST) -> Result<PathBuf, String> {
+        match self.evaluate_expression(path)? {
+            SymbolValue::String(path) => self.resolve_path(&path),
+            other => Err(format!("File path must be a string, found {:?}.", other)),
+        }
+    }
+
+    /// Resolves `path` against the file root, rejecting anything that ends up outside it.
+    fn resolve_path(&self, path: &str) -> Result<PathBuf, String> {
+        let root = self.file_root.as_ref()
+            .ok_or_else(|| format!("File I/O is disabled, cannot access '{}'.", path))?;
+        let root = root.canonicalize()
+            .map_err(|e| format!("Invalid file root '{}': {}", root.display(), e))?;
+
+        let candidate = root.join(path);
+        let resolved = match candidate.canonicalize() {
+            Ok(resolved) => resolved,
+            // The file may not exist yet (write_file), so resolve its directory instead.
+            Err(_) => {
+                // A dangling symlink fails to resolve as well, and writing would follow it.
+                if fs::symlink_metadata(&candidate).is_ok_and(|metadata| metadata.file_type().is_symlink()) {
+                    return Err(format!("Path '{}' is outside the allowed directory.", path));
+                }
+                let file_name = candidate.file_name()
+                    .ok_or_else(|| format!("Invalid file path '{}'.", path))?;
+                let parent = candidate.parent().unwrap_or(Path::new(""));
+                parent.canonicalize()
+                    .map_err(|e| format!("Failed to resolve path '{}': {}", path, e))?
+                    .join(file_name)
+            }
+        };
+
+        if resolved.starts_with(&root) {
+            Ok(resolved)
+        } else {
+            Err(format!("Path '{}' is outside the allowed directory.", path))
+        }
+    }
+
+    fn read_input(&mut self, kind: InputKind) -> Result<SymbolValue, String> {
+        let mut line = String::new();
+        let bytes_read = self.input.read_line(&mut line)
+            .map_err(|e| format!("Failed to read input: {}", e))?;
+        if bytes_read == 0 {
+            return Err("Unexpected end of input.".to_string());
+        }
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        match kind {
+            InputKind::Line => Ok(SymbolValue::String(line.to_string())),
+            InputKind::Int => line.trim().parse::<i64>()
+                .map(SymbolValue::Int)
+                .map_err(|_| format!("Invalid integer input '{}'.", line)),
+            InputKind::Float => line.trim().parse::<f64>()
+                .map(SymbolValue::Float)
+                .map_err(|_| format!("Invalid float input '{}'.", line)),
+        }
+    }
+
     fn infer_type(&self, node: &AST) -> Result<SymbolType, String> {
         match &node.node {
             ASTNode::Int(_) => Ok(SymbolType::Int),
             ASTNode::Float(_) => Ok(SymbolType::Float),
             ASTNode::Boolean(_) => Ok(SymbolType::Boolean),
+            ASTNode::String(_) => Ok(SymbolType::String),
+            ASTNode::Stdin(kind) => Ok(kind.symbol_type()),
+            ASTNode::ReadFile { .. } => Ok(SymbolType::String),
+            ASTNode::ReadLines { .. } => Ok(SymbolType::List(Box::new(SymbolType::String))),
+            ASTNode::WriteFile { .. } => Ok(SymbolType::Void),
+            ASTNode::Format(_) => Ok(SymbolType::String),
             ASTNode::Identifier(id) => {
                 self.symbol_table.lookup(id)
                     .map(|symbol| symbol.symbol_type.clone())
@@ -317,8 +751,14 @@ impl Interpreter {
                     Err("Len operation can only be performed on lists.".to_string())
                 }
             },
+            ASTNode::Let { .. } => Ok(SymbolType::Void),
             ASTNode::Assignment { expression, .. } => self.infer_type(expression),
-            ASTNode::Print(expression) => self.infer_type(expression),
+            ASTNode::Print { arguments, separator, .. } => {
+                for argument in arguments.iter().chain(separator.as_deref()) {
+                    self.infer_type(argument)?;
+                }
+                Ok(SymbolType::Void)
+            },
             ASTNode::If { condition, then_branch, else_branch } => {
                 let condition_type = self.infer_type(condition)?;
                 let then_type = self.infer_type(then_branch)?;
@@ -329,7 +769,7 @@ impl Interpreter {
                 };
     
                 if condition_type != SymbolType::Boolean {
-                    Err(format!("Condition in 'If' statement must be of type Boolean."))
+                    Err("Condition in 'If' statement must be of type Boolean.".to_string())
                 } else if then_type == else_type {
                     Ok(then_type)
                 } else {
@@ -370,7 +810,86 @@ impl Interpreter {
                 self.infer_type(body)?;
                 Ok(SymbolType::Void) 
             },
-            ASTNode::Break => Ok(SymbolType::Void), 
+            ASTNode::Break => Ok(SymbolType::Void),
+            ASTNode::Map(entries) => {
+                if entries.is_empty() {
+                    return Err("Cannot infer type of empty map.".to_string());
+                }
+                let key_type = self.infer_type(&entries[0].0)?;
+                let value_type = self.infer_type(&entries[0].1)?;
+                Ok(SymbolType::Map(Box::new(key_type), Box::new(value_type)))
+            },
+            ASTNode::Index { collection, .. } => {
+                match self.infer_type(collection)? {
+                    SymbolType::List(element_type) | SymbolType::Map(_, element_type) => Ok(*element_type),
+                    _ => Err("Indexing can only be performed on lists and maps.".to_string()),
+                }
+            },
+            ASTNode::IndexAssignment { .. } => Ok(SymbolType::Void),
+            ASTNode::Tuple(elements) => {
+                let mut element_types = Vec::new();
+                for element in elements {
+                    element_types.push(self.infer_type(element)?);
+                }
+                Ok(SymbolType::Tuple(element_types))
+            },
+            ASTNode::TupleIndex { tuple, index } => {
+                match self.infer_type(tuple)? {
+                    SymbolType::Tuple(element_types) => element_types.get(*index).cloned()
+                        .ok_or_else(|| format!("Tuple index {} out of bounds.", index)),
+                    _ => Err("Tuple indexing can only be performed on tuples.".to_string()),
+                }
+            },
+            ASTNode::Destructure { .. } => Ok(SymbolType::Void),
+            ASTNode::StructDecl { .. } | ASTNode::FieldAssignment { .. } => Ok(SymbolType::Void),
+            ASTNode::EnumDecl { .. } | ASTNode::ConstDecl { .. } => Ok(SymbolType::Void),
+            ASTNode::Try { .. } | ASTNode::Throw { .. } => Ok(SymbolType::Void),
+            ASTNode::Assert { .. } | ASTNode::AssertEq { .. } | ASTNode::Test { .. } => Ok(SymbolType::Void),
+            ASTNode::None => Ok(SymbolType::none_type()),
+            ASTNode::IsNone { .. } => Ok(SymbolType::Boolean),
+            ASTNode::UnwrapOr { value, default } => match self.infer_type(value)? {
+                SymbolType::Option(inner) if *inner != SymbolType::Void => Ok(*inner),
+                _ => self.infer_type(default),
+            },
+            ASTNode::TryPop { list: collection } | ASTNode::Get { collection, .. } => match self.infer_type(collection)? {
+                SymbolType::List(element_type) | SymbolType::Map(_, element_type) => Ok(SymbolType::Option(element_type)),
+                other => Err(format!("Optional access can only be performed on lists and maps, found {:?}.", other)),
+            },
+            ASTNode::EnumVariant { enum_type, .. } => Ok(enum_type.clone()),
+            ASTNode::Match { result_type, .. } => Ok(result_type.clone()),
+            ASTNode::StructLiteral { name, fields } => {
+                let mut field_types = Vec::new();
+                for (field, value) in fields {
+                    field_types.push((field.clone(), self.infer_type(value)?));
+                }
+                Ok(SymbolType::Struct { name: name.clone(), fields: field_types })
+            },
+            ASTNode::FieldAccess { object, field } => {
+                match self.infer_type(object)? {
+                    SymbolType::Struct { name, fields } => fields.into_iter()
+                        .find(|(declared, _)| declared == field)
+                        .map(|(_, field_type)| field_type)
+                        .ok_or_else(|| format!("Struct '{}' has no field '{}'.", name, field)),
+                    SymbolType::Error => SymbolType::error_field_type(field)
+                        .ok_or_else(|| format!("Error values have no field '{}'.", field)),
+                    _ => Err(format!("Field access '.{}' can only be performed on structs.", field)),
+                }
+            },
+            ASTNode::Keys { map } | ASTNode::Values { map } => {
+                match self.infer_type(map)? {
+                    SymbolType::Map(key_type, value_type) => Ok(SymbolType::List(
+                        if matches!(node.node, ASTNode::Keys { .. }) { key_type } else { value_type }
+                    )),
+                    _ => Err("keys and values can only be performed on maps.".to_string()),
+                }
+            },
+            ASTNode::ContainsKey { .. } => Ok(SymbolType::Boolean),
+            ASTNode::Remove { map, .. } => {
+                match self.infer_type(map)? {
+                    SymbolType::Map(_, value_type) => Ok(*value_type),
+                    _ => Err("Remove operation can only be performed on maps.".to_string()),
+                }
+            }, 
         }
     }
 }
@@ -381,10 +900,32 @@ impl fmt::Display for SymbolValue {
             SymbolValue::Int(value) => write!(f, "{}", value),
             SymbolValue::Float(value) => write!(f, "{}", value),
             SymbolValue::Boolean(value) => write!(f, "{}", value),
+            SymbolValue::String(value) => write!(f, "{}", value),
             SymbolValue::List(values) => {
                 let values_str: Vec<String> = values.iter().map(|v| v.to_string()).collect();
                 write!(f, "[{}]", values_str.join(", "))
             }
+            SymbolValue::Tuple(values) => {
+                let values_str: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "({})", values_str.join(", "))
+            }
+            SymbolValue::Struct { name, fields } => {
+                let fields_str: Vec<String> = fields.iter().map(|(field, v)| format!("{}: {}", field, v)).collect();
+                write!(f, "{} {{ {} }}", name, fields_str.join(", "))
+            }
+            SymbolValue::None => write!(f, "none"),
+            SymbolValue::Error { message, position } => {
+                write!(f, "{} at line {}, column {}", message, position.line, position.column)
+            }
+            SymbolValue::Enum { name, variant, values } if values.is_empty() => write!(f, "{}.{}", name, variant),
+            SymbolValue::Enum { name, variant, values } => {
+                let values_str: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "{}.{}({})", name, variant, values_str.join(", "))
+            }
+            SymbolValue::Map(entries) => {
+                let entries_str: Vec<String> = entries.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+                write!(f, "{{{}}}", entries_str.join(", "))
+            }
         }
     }
 }
\ No newline at end of file
@@ -28,16 +28,19 @@ fn generate_ir_node(node: &AST, instructions: &mut Vec<IRInstruction>) {
                 "-" => instructions.push(IRInstruction::Sub { dest: "R0".to_string(), src: "R1".to_string() }),
                 "*" => instructions.push(IRInstruction::Mul { dest: "R0".to_string(), src: "R1".to_string() }),
                 "/" => instructions.push(IRInstruction::Div { dest: "R0".to_string(), src: "R1".to_string() }),
+                "%" => instructions.push(IRInstruction::Mod { dest: "R0".to_string(), src: "R1".to_string() }),
                 _ => panic!("Unsupported operator"),
             }
         }
-        ASTNode::Assignment { variable, expression } => {
+        ASTNode::Let { variable, expression, .. } | ASTNode::Assignment { variable, expression } => {
             generate_ir_node(expression, instructions);
             instructions.push(IRInstruction::Mov { dest: variable.clone(), src: "R0".to_string() });
         }
-        ASTNode::Print(operand) => {
-            generate_ir_node(operand, instructions);
-            instructions.push(IRInstruction::Print { operand: "R0".to_string() });
+        ASTNode::Print { arguments, .. } => {
+            for operand in arguments {
+                generate_ir_node(operand, instructions);
+                instructions.push(IRInstruction::Print { operand: "R0".to_string() });
+            }
         }
         _ => {
             println!("Unsupported AST node: {:?}", node.node); 
@@ -10,6 +10,7 @@ pub enum IRInstruction {
     Sub { dest: String, src: String },
     Mul { dest: String, src: String },
     Div { dest: String, src: String },
+    Mod { dest: String, src: String },
     Print { operand: String },
 }
 
@@ -34,7 +35,8 @@ pub fn write_asm_file(instructions: &[IRInstruction], filename: &str) -> std::io
             IRInstruction::Add { dest, src }
             | IRInstruction::Sub { dest, src }
             | IRInstruction::Mul { dest, src }
-            | IRInstruction::Div { dest, src } => {
+            | IRInstruction::Div { dest, src }
+            | IRInstruction::Mod { dest, src } => {
                 if !variables.contains_key(dest) && !dest.starts_with("R") {
                     variables.insert(dest.clone(), format!("v{}", current_var_id));
                     current_var_id += 1;
@@ -57,7 +59,7 @@ pub fn write_asm_file(instructions: &[IRInstruction], filename: &str) -> std::io
     writeln!(file, "msg db 'Result: ', 0")?; 
     writeln!(file, "buffer db 20 dup(0)")?; 
 
-    for (_var_name, asm_name) in &variables {
+    for asm_name in variables.values() {
         writeln!(file, "{} dq 0", asm_name)?;
     }
 
@@ -94,6 +96,11 @@ pub fn write_asm_file(instructions: &[IRInstruction], filename: &str) -> std::io
                 let src_op = convert_to_register_or_memory(src, &variables);
                 format!("mov rax, {}\ncqo\nidiv {}\n", dest_op, src_op)
             },
+            IRInstruction::Mod { dest, src } => {
+                let dest_op = convert_to_register_or_memory(dest, &variables);
+                let src_op = convert_to_register_or_memory(src, &variables);
+                format!("mov rax, {}\ncqo\nidiv {}\nmov rax, rdx\n", dest_op, src_op)
+            },
             IRInstruction::Print { operand } => {
                 let operand_mem = convert_to_register_or_memory(operand, &variables);
                 format!(
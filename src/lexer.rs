@@ -1,472 +1,575 @@
 use crate::models::{Position, Token, TokenType};
+use crate::token_spec::{symbol_pattern, Word, BLANKS, BLOCK_COMMENT, KEYWORDS, SYMBOLS, TOKEN_CLASSES};
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::{Anchored, Input, MatchKind, PatternID};
+use std::borrow::Cow;
+use std::num::{ParseFloatError, ParseIntError};
+use std::ops::Range;
+use std::sync::OnceLock;
 
+/// Lexes the whole input into owned tokens. All invalid characters are reported at once.
 pub fn lexer(input: &str) -> Result<Vec<Token>, String> {
     let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
-    let mut line = 1;
-    let mut column = 1;
-
-    while let Some(&ch) = chars.peek() {
-        let token = match ch {
-            ' ' | '\t' => {
-                chars.next();
-                column += 1;
-                continue;
-            }
-            '\n' => {
-                chars.next();
-                line += 1;
-                column = 1;
-                continue;
-            }
-            '/' if chars.clone().nth(1) == Some('/') => {
-                while let Some(&next_ch) = chars.peek() {
-                    if next_ch == '\n' {
-                        break;
-                    }
-                    chars.next();
-                }
-                continue;
-            }
-            '0'..='9' => {
-                let start_column = column;
-                let mut num = String::new();
-                let mut has_dot = false;
-
-                while let Some(&digit) = chars.peek() {
-                    if digit.is_numeric() {
-                        num.push(chars.next().unwrap());
-                        column += 1;
-                    } else if digit == '.' {
-                        if has_dot {
-                            break;
-                        }
-                        has_dot = true;
-                        num.push(chars.next().unwrap());
-                        column += 1;
-                    } else {
-                        break;
-                    }
-                }
+    let mut errors = Vec::new();
+    for token in Lexer::new(input) {
+        match token {
+            Ok(token) => tokens.push(Token::from(token)),
+            Err(e) => errors.push(e),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    Ok(tokens)
+}
 
-                Token::new(
-                    if has_dot {
-                        TokenType::FloatNumber
-                    } else {
-                        TokenType::Number
-                    },
-                    num,
-                    Position {
-                        line,
-                        column: start_column,
-                    },
-                )
-            }
+/// A token that borrows its text from the source instead of owning a copy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RawToken<'src> {
+    pub token_type: TokenType,
+    /// The token exactly as written, e.g. a string literal with its quotes and escapes.
+    pub text: &'src str,
+    pub position: Position,
+    /// Byte offset of `text` in the source.
+    pub offset: usize,
+}
 
-            'a'..='z' | 'A'..='Z' | '_' => {
-                let start_column = column;
-                let mut ident = String::new();
-                while let Some(&letter) = chars.peek() {
-                    if letter.is_alphanumeric() || letter == '_' {
-                        ident.push(chars.next().unwrap());
-                        column += 1;
-                    } else {
-                        break;
-                    }
-                }
-                let token_type = match ident.as_str() {
-                    "let" => TokenType::Let,
-                    "stdout" => TokenType::Print,
-                    "if" => TokenType::If,
-                    "then" => TokenType::Then,
-                    "else" => TokenType::Else,
-                    "end" => TokenType::End,
-                    "true" | "false" => TokenType::Boolean, // Handle boolean literals
-                    "int" => TokenType::IntType,
-                    "float" => TokenType::FloatType,
-                    "bool" => TokenType::BoolType,
-                    "fetch" => TokenType::Fetch,
-                    "push" => TokenType::Push,
-                    "pop" => TokenType::Pop,
-                    "loop" => TokenType::Loop,
-                    "len" => TokenType::Len,
-                    "break" => TokenType::Break,
-                    _ => TokenType::Identifier,
-                };
-                Token::new(
-                    token_type,
-                    ident,
-                    Position {
-                        line,
-                        column: start_column,
-                    },
-                )
-            }
-            '{' => {
-                // Add this block
-                let token = Token::new(
-                    TokenType::LeftBrace,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
+impl<'src> RawToken<'src> {
+    pub fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.text.len()
+    }
 
-            '}' => {
-                let token = Token::new(
-                    TokenType::RightBrace,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
-            ':' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                if chars.peek() == Some(&'=') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::Assign,
-                        ":=".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else if chars.peek() == Some(&'[') {
-                    chars.next();
-                    column += 1;
-                    let mut type_str = String::new();
-                    while let Some(&ch) = chars.peek() {
-                        if ch.is_alphabetic() {
-                            type_str.push(chars.next().unwrap());
-                            column += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    if chars.peek() == Some(&']') {
-                        chars.next();
-                        column += 1;
-                        let token_type = match type_str.as_str() {
-                            "int" => TokenType::ListIntType,
-                            "float" => TokenType::ListFloatType,
-                            "bool" => TokenType::ListBoolType,
-                            _ => TokenType::Unknown,
-                        };
-                        Token::new(
-                            token_type,
-                            format!(":[{}]", type_str),
-                            Position {
-                                line,
-                                column: start_column,
-                            },
-                        )
-                    } else {
-                        Token::new(
-                            TokenType::Unknown,
-                            format!(":[{}]", type_str),
-                            Position {
-                                line,
-                                column: start_column,
-                            },
-                        )
-                    }
+    /// The value `Token::value` holds: string literals lose their quotes and have their
+    /// escapes resolved, doc comments lose their `///`. Only escaped strings allocate.
+    pub fn value(&self) -> Cow<'src, str> {
+        match self.token_type {
+            TokenType::StringLiteral => {
+                let body = &self.text[1..self.text.len() - 1];
+                if body.contains('\\') {
+                    Cow::Owned(unescape(body))
                 } else {
-                    let mut type_str = String::new();
-                    while let Some(&ch) = chars.peek() {
-                        if ch.is_alphabetic() {
-                            type_str.push(chars.next().unwrap());
-                            column += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    let token_type = match type_str.as_str() {
-                        "int" => TokenType::IntType,
-                        "float" => TokenType::FloatType,
-                        "bool" => TokenType::BoolType,
-                        _ => TokenType::Unknown,
-                    };
-                    Token::new(
-                        token_type,
-                        format!(":{}", type_str),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
+                    Cow::Borrowed(body)
                 }
             }
-
-            '+' | '-' | '*' | '/' => {
-                let token = Token::new(
-                    TokenType::Operator,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
+            TokenType::DocComment => {
+                let text = &self.text[3..];
+                Cow::Borrowed(text.strip_prefix(' ').unwrap_or(text).trim_end())
             }
+            _ => Cow::Borrowed(self.text),
+        }
+    }
+}
 
-            '(' => {
-                let token = Token::new(
-                    TokenType::LeftParen,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
+impl From<RawToken<'_>> for Token {
+    fn from(raw: RawToken<'_>) -> Self {
+        let mut token = Token::new(raw.token_type, raw.value().into_owned(), raw.position);
+        token.span = raw.span();
+        token
+    }
+}
 
-            ')' => {
-                let token = Token::new(
-                    TokenType::RightParen,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
+/// Resolves the escapes in a string literal body the lexer has already validated.
+fn unescape(body: &str) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut chars = body.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('t') => text.push('\t'),
+                Some(other) => text.push(other),
+                None => {}
+            },
+            _ => text.push(ch),
+        }
+    }
+    text
+}
 
-            ';' => {
-                let token = Token::new(
-                    TokenType::Termination,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
+/// A file that starts with this comment is lexed with semicolon insertion.
+pub const AUTO_SEMICOLONS_PRAGMA: &str = "// semicolons: auto";
 
-            '>' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                let token = if chars.peek() == Some(&'=') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::GreaterThanOrEqual,
-                        ">=".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::GreaterThan,
-                        ">".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                };
-                token
-            }
+/// `token_spec`'s tables, compiled once for every `Lexer` to share.
+struct Tables {
+    /// One pattern per entry of `TOKEN_CLASSES`, then one for all `SYMBOLS`. An anchored
+    /// search finds the first pattern matching at the cursor and where its match ends,
+    /// reading each byte once.
+    dfa: DFA,
+    /// The pattern in `dfa` matching `SYMBOLS`.
+    symbols: PatternID,
+    symbol_types: WordTable,
+    keywords: WordTable,
+}
 
-            '<' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                let token = if chars.peek() == Some(&'=') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::LessThanOrEqual,
-                        "<=".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::LessThan,
-                        "<".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                };
-                token
-            }
+/// Fixed words grouped by their first byte, so a lookup compares only a few of them.
+struct WordTable(Vec<Vec<(&'static str, TokenType)>>);
 
-            '=' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                if chars.peek() == Some(&'=') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::Equal,
-                        "==".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::Operator,
-                        "=".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                }
+impl WordTable {
+    fn new(words: &[Word]) -> Self {
+        let mut groups = vec![Vec::new(); 256];
+        for (word, token_type, _) in words {
+            groups[usize::from(word.as_bytes()[0])].push((*word, *token_type));
+        }
+        WordTable(groups)
+    }
+
+    fn get(&self, text: &str) -> Option<TokenType> {
+        let group = &self.0[usize::from(*text.as_bytes().first()?)];
+        group.iter().find(|(word, _)| *word == text).map(|(_, token_type)| *token_type)
+    }
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let symbols = symbol_pattern(SYMBOLS.iter().map(|(symbol, _, _)| *symbol));
+        let mut patterns: Vec<&str> = TOKEN_CLASSES.iter().map(|class| class.pattern).collect();
+        patterns.push(&symbols);
+        let dfa = DFA::builder()
+            .configure(DFA::config().match_kind(MatchKind::LeftmostFirst).starts_for_each_pattern(true))
+            .build_many(&patterns)
+            .expect("token patterns are valid");
+        Tables {
+            dfa,
+            symbols: PatternID::must(TOKEN_CLASSES.len()),
+            symbol_types: WordTable::new(SYMBOLS),
+            keywords: WordTable::new(KEYWORDS),
+        }
+    })
+}
+
+/// Streams tokens from the source without copying their text, ending with `EOF`.
+/// An invalid character yields an error and lexing carries on past it; any other
+/// error (an unterminated string or comment, a malformed number) ends the stream.
+pub struct Lexer<'src> {
+    source: &'src str,
+    /// Whether a newline ends a statement; see `with_semicolon_insertion`.
+    auto_semicolons: bool,
+    /// For each open `(`, `[` and `{`, whether it holds statements, as only the braces of a
+    /// `loop`, `try`, `catch` or `test` do. A newline directly inside any other, such as a
+    /// map, struct literal or struct body, never ends a statement.
+    open: Vec<bool>,
+    /// Whether the next `{` opens a block: a `loop`, `try`, `catch` or `test` came before it.
+    block_follows: bool,
+    /// Byte offset of the next character.
+    offset: usize,
+    line: usize,
+    column: usize,
+    /// The last token's type, which decides how a following digit or `.` is read.
+    previous: Option<TokenType>,
+    finished: bool,
+    tables: &'static Tables,
+    /// The states of `Tables::dfa` this lexer has built so far.
+    cache: Cache,
+}
+
+impl<'src> Lexer<'src> {
+    pub fn new(source: &'src str) -> Self {
+        Lexer {
+            source,
+            auto_semicolons: false,
+            open: Vec::new(),
+            block_follows: false,
+            offset: 0,
+            line: 1,
+            column: 1,
+            previous: None,
+            finished: false,
+            tables: tables(),
+            cache: tables().dfa.create_cache(),
+        }
+    }
+
+    /// Inserts a `;` at each newline that follows a token able to end a statement (an
+    /// identifier, a literal, `break`, `end`, `)`, `]` or `}`), unless the newline is inside
+    /// parentheses, brackets or braces other than a block's; likewise at the end of the source. The inserted token's text
+    /// is the newline, or empty at the end. A file turns this on by starting with a
+    /// `// semicolons: auto` comment.
+    pub fn with_semicolon_insertion(mut self, enabled: bool) -> Self {
+        self.auto_semicolons = enabled;
+        self
+    }
+
+    /// The text being lexed.
+    pub fn source(&self) -> &'src str {
+        self.source
+    }
+
+    fn inserts_semicolon(&self) -> bool {
+        self.auto_semicolons
+            && self.open.last().is_none_or(|block| *block)
+            && (ends_operand(self.previous) || matches!(self.previous, Some(TokenType::Break | TokenType::End)))
+    }
+
+    fn cursor(&self) -> Position {
+        Position { line: self.line, column: self.column }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.offset..].chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.source[self.offset..].chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.offset += ch.len_utf8();
+        if ch == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(ch)
+    }
+
+    fn bump_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+    }
+
+    /// The source from `start` up to the cursor.
+    fn text_from(&self, start: usize) -> &'src str {
+        let source = self.source;
+        &source[start..self.offset]
+    }
+
+    /// Reports an error that ends the stream.
+    fn fail<T>(&mut self, message: String) -> Result<T, String> {
+        self.finished = true;
+        Err(message)
+    }
+
+    /// Moves the cursor to byte offset `end`, counting the lines and columns in between.
+    fn advance_to(&mut self, end: usize) {
+        for byte in &self.source.as_bytes()[self.offset..end] {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if !is_utf8_continuation(*byte) {
+                self.column += 1;
             }
+        }
+        self.offset = end;
+    }
 
-            '!' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                if chars.peek() == Some(&'=') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::NotEqual,
-                        "!=".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::Operator,
-                        "!".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                }
+    /// Runs `Tables::dfa` from byte `start` and returns the pattern that matched and where
+    /// its match ends. Stepping the DFA directly avoids a full search's setup, which would
+    /// otherwise cost more than the few bytes of a typical token.
+    fn find_token(&mut self, start: usize, anchored: Anchored) -> Result<Option<(PatternID, usize)>, String> {
+        let dfa = &self.tables.dfa;
+        let cache = &mut self.cache;
+        let bytes = self.source.as_bytes();
+        let input = Input::new(bytes).range(start..).anchored(anchored);
+        let mut state = dfa.start_state_forward(cache, &input).map_err(|e| e.to_string())?;
+        let mut found = None;
+        // A match state is entered one byte past the end of its match.
+        for (end, byte) in bytes.iter().enumerate().skip(start) {
+            state = dfa.next_state(cache, state, *byte).map_err(|e| e.to_string())?;
+            if state.is_match() {
+                found = Some((dfa.match_pattern(cache, state, 0), end));
+            } else if state.is_dead() {
+                return Ok(found);
             }
+        }
+        state = dfa.next_eoi_state(cache, state).map_err(|e| e.to_string())?;
+        if state.is_match() {
+            found = Some((dfa.match_pattern(cache, state, 0), bytes.len()));
+        }
+        Ok(found)
+    }
 
-            '&' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                if chars.peek() == Some(&'&') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::And,
-                        "&&".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::Operator,
-                        "&".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
+    /// Scans the token starting at `ch`, or skips a comment and returns `None`.
+    fn scan(&mut self, ch: char, start: usize, position: Position) -> Result<Option<TokenType>, String> {
+        let tables = self.tables;
+        if ch.is_ascii_digit() && self.previous == Some(TokenType::Dot) {
+            // A tuple index: `t.0.1` is two indices, not the float `0.1`.
+            self.bump_while(|ch| ch.is_ascii_digit());
+            return Ok(Some(TokenType::Number));
+        }
+        if self.source[start..].starts_with(BLOCK_COMMENT.0) {
+            self.block_comment(position)?;
+            return Ok(None);
+        }
+
+        // After an operand, `.5` is field or tuple access rather than a float.
+        let anchored = if ch == '.' && ends_operand(self.previous) {
+            Anchored::Pattern(tables.symbols)
+        } else {
+            Anchored::Yes
+        };
+        let found = match self.find_token(start, anchored) {
+            Ok(found) => found,
+            Err(e) => return self.fail(format!("Failed to lex at position {:?}: {}", position, e)),
+        };
+        if let Some((pattern, end)) = found {
+            let Some(class) = TOKEN_CLASSES.get(pattern.as_usize()) else {
+                self.advance_to(end);
+                return Ok(tables.symbol_types.get(&self.source[start..end]));
+            };
+            return match class.token_type {
+                Some(TokenType::StringLiteral) => self.string_literal(start, end, position).map(Some),
+                Some(token_type @ (TokenType::Number | TokenType::FloatNumber)) => {
+                    self.advance_to(end);
+                    self.number(token_type, start, position).map(Some)
                 }
-            }
+                Some(TokenType::Identifier) => {
+                    self.advance_to(end);
+                    Ok(Some(tables.keywords.get(&self.source[start..end]).unwrap_or(TokenType::Identifier)))
+                }
+                token_type => {
+                    self.advance_to(end);
+                    Ok(token_type)
+                }
+            };
+        }
 
-            '|' => {
-                let start_column = column;
-                chars.next();
-                column += 1;
-                if chars.peek() == Some(&'|') {
-                    chars.next();
-                    column += 1;
-                    Token::new(
-                        TokenType::Or,
-                        "||".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
-                } else {
-                    Token::new(
-                        TokenType::Operator,
-                        "|".to_string(),
-                        Position {
-                            line,
-                            column: start_column,
-                        },
-                    )
+        self.bump();
+        Err(match ch {
+            '=' => format!(
+                "Unexpected '=' at position {:?}. Use ':=' for assignment or '==' for comparison.",
+                position
+            ),
+            '&' => format!("Unexpected '&' at position {:?}. Did you mean '&&'?", position),
+            '|' => format!("Unexpected '|' at position {:?}. Did you mean '||'?", position),
+            _ => format!("Invalid character '{}' at position {:?}.", ch, position),
+        })
+    }
+
+    fn block_comment(&mut self, position: Position) -> Result<(), String> {
+        let (open, close) = BLOCK_COMMENT;
+        let mut depth = 0;
+        while self.offset < self.source.len() {
+            let rest = &self.source[self.offset..];
+            if rest.starts_with(open) {
+                self.advance_to(self.offset + open.len());
+                depth += 1;
+            } else if rest.starts_with(close) {
+                self.advance_to(self.offset + close.len());
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
                 }
+            } else {
+                self.bump();
             }
+        }
+        self.fail(format!("Unterminated block comment at position {:?}.", position))
+    }
 
-            '[' => {
-                let token = Token::new(
-                    TokenType::LeftBracket,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
+    /// Checks the escapes of the string literal matched at `start..end` and that it is closed.
+    fn string_literal(&mut self, start: usize, end: usize, position: Position) -> Result<TokenType, String> {
+        let text = &self.source[start..end];
+        let mut chars = text.char_indices().skip(1);
+        let mut closed = false;
+        while let Some((index, ch)) = chars.next() {
+            match ch {
+                '\\' => match chars.next() {
+                    Some((_, 'n' | 't' | '"' | '\\')) | None => {}
+                    Some((_, other)) => {
+                        self.advance_to(start + index);
+                        let escape_position = self.cursor();
+                        return self.fail(format!(
+                            "Unknown escape sequence '\\{}' at position {:?}.",
+                            other, escape_position
+                        ));
+                    }
+                },
+                '"' => closed = true,
+                _ => {}
             }
+        }
+        self.advance_to(end);
+        if !closed {
+            return self.fail(format!("Unterminated string literal at position {:?}.", position));
+        }
+        Ok(TokenType::StringLiteral)
+    }
 
-            ']' => {
-                let token = Token::new(
-                    TokenType::RightBracket,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
-            }
+    /// Checks a matched numeric literal: `12`, `1_000`, `0xFF`, `0o17`, `0b1010`, `1.5`, `.5`
+    /// or `1e-3`. The token keeps the source text, which `int_literal_value` and
+    /// `float_literal_value` convert.
+    fn number(&mut self, token_type: TokenType, start: usize, position: Position) -> Result<TokenType, String> {
+        let text = self.text_from(start);
+        if token_type == TokenType::FloatNumber && text.ends_with(['e', 'E', '+', '-']) {
+            return self.fail(format!(
+                "Missing exponent digits in number literal '{}' at position {:?}.",
+                text, position
+            ));
+        }
 
-            ',' => {
-                let token = Token::new(TokenType::Comma, ch.to_string(), Position { line, column });
-                chars.next();
-                column += 1;
-                token
+        let radix = match text.get(..2) {
+            Some("0x" | "0X") => Some((16, "hexadecimal")),
+            Some("0o" | "0O") => Some((8, "octal")),
+            Some("0b" | "0B") => Some((2, "binary")),
+            _ => None,
+        };
+        if let Some((radix, name)) = radix {
+            let digits = &text[2..];
+            if let Some(invalid) = digits.chars().find(|ch| *ch != '_' && !ch.is_digit(radix)) {
+                return self.fail(format!(
+                    "Invalid digit '{}' in {} literal '{}' at position {:?}.",
+                    invalid, name, text, position
+                ));
             }
-
-            '.' => {
-                let token = Token::new(TokenType::Dot, ch.to_string(), Position { line, column });
-                chars.next();
-                column += 1;
-                token
+            if !digits.chars().any(|ch| ch.is_digit(radix)) {
+                return self.fail(format!("Missing digits in {} literal '{}' at position {:?}.", name, text, position));
             }
+            return self.checked_int(text, radix, position);
+        }
+
+        if self.peek() == Some('.') && self.peek_second().is_some_and(|ch| ch.is_ascii_digit()) {
+            self.bump_while(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch == '_');
+            let text = self.text_from(start);
+            return self.fail(format!("Malformed number literal '{}' at position {:?}.", text, position));
+        }
+        if let Some(ch) = self.peek().filter(|ch| ch.is_alphanumeric()) {
+            return self.fail(format!(
+                "Invalid character '{}' in number literal '{}{}' at position {:?}.",
+                ch, text, ch, position
+            ));
+        }
+        if token_type == TokenType::Number {
+            return self.checked_int(text, 10, position);
+        }
+        if let Err(e) = check_separators(text, 10, position) {
+            return self.fail(e);
+        }
+        match float_literal_value(text) {
+            Ok(value) if value.is_infinite() => self.fail(format!(
+                "Float literal '{}' is out of range for float at position {:?}.",
+                text, position
+            )),
+            Ok(_) => Ok(TokenType::FloatNumber),
+            Err(_) => self.fail(format!("Invalid float literal '{}' at position {:?}.", text, position)),
+        }
+    }
+
+    fn checked_int(&mut self, text: &str, radix: u32, position: Position) -> Result<TokenType, String> {
+        if let Err(e) = check_separators(text, radix, position) {
+            return self.fail(e);
+        }
+        if int_literal_value(text).is_err() {
+            return self.fail(format!(
+                "Integer literal '{}' is out of range for int at position {:?}.",
+                text, position
+            ));
+        }
+        Ok(TokenType::Number)
+    }
+}
 
-            _ => {
-                let token = Token::new(
-                    TokenType::Unknown,
-                    ch.to_string(),
-                    Position { line, column },
-                );
-                chars.next();
-                column += 1;
-                token
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Result<RawToken<'src>, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let start = self.offset;
+            let position = self.cursor();
+            let next = self.peek();
+            if matches!(next, Some('\n') | None) && self.inserts_semicolon() {
+                self.bump();
+                self.previous = Some(TokenType::Termination);
+                let text = self.text_from(start);
+                return Some(Ok(RawToken { token_type: TokenType::Termination, text, position, offset: start }));
             }
-        };
-        tokens.push(token);
+            let Some(ch) = next else {
+                self.finished = true;
+                return Some(Ok(RawToken { token_type: TokenType::EOF, text: "", position, offset: start }));
+            };
+            let bytes = self.source.as_bytes();
+            if BLANKS.contains(&bytes[start]) {
+                // Stop at a newline that ends a statement, so the check above inserts its `;`.
+                let inserts_semicolon = self.inserts_semicolon();
+                let blanks = bytes[start..]
+                    .iter()
+                    .take_while(|byte| BLANKS.contains(byte) && !(**byte == b'\n' && inserts_semicolon))
+                    .count();
+                self.advance_to(start + blanks);
+                continue;
+            }
+            match self.scan(ch, start, position) {
+                Ok(None) => {
+                    if self.previous.is_none() && self.text_from(start).trim_end() == AUTO_SEMICOLONS_PRAGMA {
+                        self.auto_semicolons = true;
+                    }
+                }
+                Ok(Some(token_type)) => {
+                    self.previous = Some(token_type);
+                    match token_type {
+                        TokenType::Loop | TokenType::Try | TokenType::Catch | TokenType::Test => self.block_follows = true,
+                        TokenType::LeftBrace => self.open.push(std::mem::take(&mut self.block_follows)),
+                        TokenType::LeftParen | TokenType::LeftBracket => self.open.push(false),
+                        TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => {
+                            self.open.pop();
+                        }
+                        _ => {}
+                    }
+                    let text = self.text_from(start);
+                    return Some(Ok(RawToken { token_type, text, position, offset: start }));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        None
     }
-    tokens.push(Token::new(
-        TokenType::EOF,
-        "".to_string(),
-        Position { line, column },
-    ));
+}
 
-    Ok(tokens)
+/// Whether `byte` continues a multi-byte character rather than starting one.
+fn is_utf8_continuation(byte: u8) -> bool {
+    byte & 0b1100_0000 == 0b1000_0000
+}
+
+/// Whether a token of type `previous` can end an operand, so that a following `.` is field
+/// or tuple access rather than the start of a literal like `.5`.
+fn ends_operand(previous: Option<TokenType>) -> bool {
+    previous.is_some_and(|token_type| matches!(
+        token_type,
+        TokenType::Identifier | TokenType::Number | TokenType::FloatNumber | TokenType::StringLiteral
+            | TokenType::Boolean | TokenType::None | TokenType::RightParen | TokenType::RightBracket
+            | TokenType::RightBrace
+    ))
+}
+
+/// A `_` separator must sit between two digits.
+fn check_separators(text: &str, radix: u32, position: Position) -> Result<(), String> {
+    // Number literals are ASCII, as their patterns only match ASCII.
+    let bytes = text.as_bytes();
+    let is_digit = |index: Option<usize>| {
+        index.and_then(|i| bytes.get(i)).is_some_and(|byte| char::from(*byte).is_digit(radix))
+    };
+    for (i, _) in bytes.iter().enumerate().filter(|(_, byte)| **byte == b'_') {
+        if !is_digit(i.checked_sub(1)) || !is_digit(Some(i + 1)) {
+            return Err(format!("Misplaced '_' in number literal '{}' at position {:?}.", text, position));
+        }
+    }
+    Ok(())
+}
+
+/// The literal's digits without `_` separators, copied only if it has any.
+fn without_separators(text: &str) -> Cow<'_, str> {
+    if text.contains('_') {
+        Cow::Owned(text.replace('_', ""))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Converts the text of a `Number` token to its value.
+pub fn int_literal_value(text: &str) -> Result<i64, ParseIntError> {
+    let digits = without_separators(text);
+    match digits.get(..2) {
+        Some("0x" | "0X") => i64::from_str_radix(&digits[2..], 16),
+        Some("0o" | "0O") => i64::from_str_radix(&digits[2..], 8),
+        Some("0b" | "0B") => i64::from_str_radix(&digits[2..], 2),
+        _ => digits.parse(),
+    }
+}
+
+/// Converts the text of a `FloatNumber` token to its value.
+pub fn float_literal_value(text: &str) -> Result<f64, ParseFloatError> {
+    without_separators(text).parse()
 }
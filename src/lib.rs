@@ -1,10 +1,13 @@
 pub mod lexer ;
+pub mod token_spec;
 pub mod models;
 pub mod errors; 
 pub mod util;
 pub mod parser;
+pub mod cst;
 pub mod ast;
 pub mod symbol_table;
 pub mod ir;
 pub mod interpreter;
 pub mod repr;
+pub mod format;
@@ -1,19 +1,21 @@
-mod errors;
-mod lexer;
-mod models;
-mod parser;
-mod symbol_table;
-//mod ir;
-mod ast;
-mod interpreter;
-
-use crate::parser::Parser;
-//use crate::ir::generator::generate_ir;
-//use crate::ir::instruction::write_asm_file;
-use crate::interpreter::Interpreter;
+use calru::lexer;
+use calru::parser::Parser;
+//use calru::ir::generator::generate_ir;
+//use calru::ir::instruction::write_asm_file;
+use calru::interpreter::Interpreter;
+use calru::token_spec::highlighting_grammar;
+use std::env;
 use std::fs;
+use std::process;
 
 fn main() {
+    // `cargo run -- grammar` prints the TextMate highlighting grammar for editors.
+    if env::args().nth(1).as_deref() == Some("grammar") {
+        print!("{}", highlighting_grammar());
+        return;
+    }
+    // `cargo run -- test` runs the file's test blocks instead of the program.
+    let test_mode = env::args().nth(1).as_deref() == Some("test");
     let input =
         fs::read_to_string("input/main.cru").expect("should have been able to read the file");
     //    println!("{}", input);
@@ -21,7 +23,7 @@ fn main() {
     match lexer::lexer(&input) {
         Ok(tokens) => {
 
-            let mut parser = Parser::new(tokens);
+            let mut parser = Parser::new(tokens).with_source(&input);
             match parser.parse_program() {
                 Ok((asts, symbol_table)) => {
                     // Ensure parse_program returns both
@@ -41,7 +43,19 @@ fn main() {
                     }
                     */
 
-                    let mut interpreter = Interpreter::new(symbol_table);
+                    let mut interpreter = Interpreter::new(symbol_table).with_file_root(".");
+
+                    if test_mode {
+                        match interpreter.run_tests(asts) {
+                            Ok(outcomes) if outcomes.iter().all(|outcome| outcome.result.is_ok()) => {},
+                            Ok(_) => process::exit(1),
+                            Err(e) => {
+                                eprintln!("Execution error: {}", e);
+                                process::exit(1);
+                            }
+                        }
+                        return;
+                    }
 
                     for ast in asts {
                         if let Err(e) = interpreter.run(vec![ast]) {
@@ -1,9 +1,13 @@
+use std::ops::Range;
+
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct Token {
     pub token_type: TokenType,
     pub value: String,
     pub position : Position,
+    /// Byte range of the token's source text, so `&source[token.span.clone()]` is what was written.
+    pub span: Range<usize>,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -11,20 +15,20 @@ pub enum TokenType {
     Identifier,
     Number,
     FloatNumber,
+    StringLiteral,
     Boolean, // Add boolean token type
     Let,
     Operator,
     Assign,
-    Unknown,
+    CompoundAssign,
+    DocComment,
     Termination,
     EOF,
     Print,
+    PrintInline,
+    PrintError,
     RightParen,
     LeftParen,
-    // Data types
-    IntType,
-    FloatType,
-    BoolType, // Add boolean type
     // If statement
     If,
     Then,
@@ -45,9 +49,6 @@ pub enum TokenType {
     RightBracket, // Add right bracket for list
     Comma,        // Add comma for list elements
     Colon, 
-    ListIntType,   // Add this line
-    ListFloatType, // Add this line
-    ListBoolType,
     Len,
     Dot,           // Add dot for method calls
     Fetch,
@@ -57,20 +58,53 @@ pub enum TokenType {
     Break, 
     LeftBrace,
     RightBrace,
+    // Input builtins
+    StdinLine,
+    StdinInt,
+    StdinFloat,
+    // File builtins
+    ReadFile,
+    ReadLines,
+    WriteFile,
+    Struct,
+    Enum,
+    Match,
+    FatArrow,
+    Question,
+    None,
+    Try,
+    Catch,
+    Throw,
+    Assert,
+    AssertEq,
+    Test,
+    Mut,
+    Const,
 }
 
+/// A 1-based line and column, where the column counts `char`s.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
     pub line: usize,
     pub column: usize,
 }
 
+impl Position {
+    /// The 1-based column in UTF-16 code units, as editors and LSP clients count them.
+    pub fn utf16_column(&self, source: &str) -> usize {
+        let line = source.split('\n').nth(self.line - 1).unwrap_or("");
+        1 + line.chars().take(self.column - 1).map(char::len_utf16).sum::<usize>()
+    }
+}
+
 impl Token {
+    /// Creates a token with an empty span; the lexer fills in the span once the token ends.
     pub fn new(token_type: TokenType, value: String, position: Position) -> Self {
         Token {
             token_type,
             value,
             position,
+            span: 0..0,
         }
     }
 }
@@ -1,31 +1,153 @@
 
 use crate::models::{TokenType, Token, Position};
-use crate::ast::{AST, ASTNode};
-use crate::symbol_table::{SymbolTable, SymbolType, SymbolValue};
+use crate::ast::{AST, ASTNode, FormatSegment, InputKind, MatchArm, OutputStream, Pattern};
+use crate::cst::NodeKind;
+use crate::format::{self, RawSegment};
+use crate::lexer::{float_literal_value, int_literal_value, lexer, Lexer};
+use crate::symbol_table::{Mutability, Symbol, SymbolTable, SymbolType, SymbolValue};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// The parser's token source: an already lexed `Vec`, or a `Lexer` read on demand.
+struct TokenStream<'src> {
+    tokens: Box<dyn Iterator<Item = Result<Token, String>> + 'src>,
+    /// Tokens read ahead of the current one, to see what a doc comment documents.
+    lookahead: VecDeque<Token>,
+    /// Lexer errors met so far; `parse_program` reports them ahead of any parse error.
+    errors: Vec<String>,
+    /// How many tokens `next` has handed out.
+    consumed: usize,
+}
+
+impl<'src> TokenStream<'src> {
+    fn new(tokens: impl Iterator<Item = Result<Token, String>> + 'src) -> Self {
+        TokenStream { tokens: Box::new(tokens), lookahead: VecDeque::new(), errors: Vec::new(), consumed: 0 }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.lookahead.pop_front().or_else(|| self.read());
+        self.consumed += usize::from(token.is_some());
+        token
+    }
+
+    fn peek(&mut self, n: usize) -> Option<&Token> {
+        while self.lookahead.len() <= n {
+            let token = self.read()?;
+            self.lookahead.push_back(token);
+        }
+        self.lookahead.get(n)
+    }
+
+    /// Reads the next token from the source, setting lexer errors aside.
+    fn read(&mut self) -> Option<Token> {
+        loop {
+            match self.tokens.next()? {
+                Ok(token) => return Some(token),
+                Err(e) => self.errors.push(e),
+            }
+        }
+    }
+
+    /// Lexes the rest of the source so that every lexer error is known.
+    fn finish(&mut self) {
+        while self.read().is_some() {}
+    }
+}
+
+impl fmt::Debug for TokenStream<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenStream")
+            .field("lookahead", &self.lookahead)
+            .field("errors", &self.errors)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A step in building the syntax tree, recorded as the parser goes for `cst` to build its
+/// nodes from. Token indices count every token the parser was given, doc comments included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Event {
+    /// A node of `kind` starts at token `token`.
+    Start { kind: NodeKind, token: usize },
+    /// The innermost open node ends before token `token`.
+    Finish { token: usize },
+}
+
+/// Where a syntax node may start. A production's node is only recorded once it parses, so
+/// `Parser::finish_node` inserts its `Start` event here after the fact.
+#[derive(Debug, Clone, Copy)]
+struct Marker {
+    event: usize,
+    token: usize,
+}
 
 #[derive(Debug)]
-pub struct Parser {
-    tokens: Vec<Token>,
-    current_index: usize,
+pub struct Parser<'src> {
+    tokens: TokenStream<'src>,
     pub current_token: Option<Token>,
     pub position: Position,
     pub symbol_table: SymbolTable,
+    /// Declared struct types by name, each a `SymbolType::Struct`.
+    pub structs: HashMap<String, SymbolType>,
+    /// Declared enum types by name, each a `SymbolType::Enum`.
+    pub enums: HashMap<String, SymbolType>,
+    /// The syntax nodes of everything parsed so far. A production that fails records none.
+    pub(crate) events: Vec<Event>,
+    /// The index just past the last token consumed, where a node finished now ends.
+    consumed_end: usize,
+    /// The text the tokens were lexed from, if known; see `with_source`.
+    source: Option<&'src str>,
 }
 
-impl Parser {
+impl<'src> Parser<'src> {
     pub fn new(tokens: Vec<Token>) -> Self {
+        Self::with_stream(TokenStream::new(tokens.into_iter().map(Ok)))
+    }
+
+    /// Parses tokens as `lexer` produces them, without collecting them first; each becomes
+    /// an owned `Token` as the parser reaches it. Lexer errors are reported by `parse_program`
+    /// just as `lexer` would report them.
+    pub fn from_lexer(lexer: Lexer<'src>) -> Self {
+        let source = lexer.source();
+        Self::with_stream(TokenStream::new(lexer.map(|token| token.map(Token::from)))).with_source(source)
+    }
+
+    /// Gives the source the tokens were lexed from, so that errors inside a string literal
+    /// point at the text as written, escapes included, rather than at its unescaped value.
+    pub fn with_source(mut self, source: &'src str) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    fn with_stream(tokens: TokenStream<'src>) -> Self {
         let mut parser = Parser {
             tokens,
-            current_index: 0,
             current_token: None,
             position: Position { line: 1, column: 1 },
             symbol_table: SymbolTable::new(),
+            structs: HashMap::new(),
+            enums: HashMap::new(),
+            events: Vec::new(),
+            consumed_end: 0,
+            source: None,
         };
         parser.advance();
         parser
     }
 
     pub fn parse_program(&mut self) -> Result<(Vec<AST>, SymbolTable), String> {
+        let result = self.parse_statements();
+        // A parse error is often a symptom of a lexer error further on; report the cause.
+        if result.is_err() {
+            self.tokens.finish();
+        }
+        if !self.tokens.errors.is_empty() {
+            return Err(self.tokens.errors.join("\n"));
+        }
+        result
+    }
+
+    fn parse_statements(&mut self) -> Result<(Vec<AST>, SymbolTable), String> {
         let mut asts = Vec::new();
 
         while let Some(ref token) = self.current_token {
@@ -60,24 +182,155 @@ impl Parser {
             expression: Box::new(expression),
         }))
     }
+    /// Parses `('+=' | '-=' | '*=' | '/=' | '%=') Expression ';'` after an identifier and lowers
+    /// it to `Identifier ':=' Identifier op Expression`. On a list, `+=` appends one element.
+    pub fn parse_compound_assignment(&mut self, variable: String) -> Result<AST, String> {
+        let token = self.current_token.clone().unwrap();
+        let operator = token.value.trim_end_matches('=').to_string();
+        self.advance(); // Consume the operator
+
+        let mut expression = self.parse_expression()?;
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        let target = AST::new(ASTNode::Identifier(variable.clone()));
+        let target_type = self.infer_type(&target)?;
+        if let (SymbolType::List(element_type), "+") = (&target_type, operator.as_str()) {
+            let value_type = self.infer_type_as(&expression, element_type)?;
+            if !element_type.accepts(&value_type) {
+                return Err(format!(
+                    "Type mismatch: cannot append value of type {:?} to list '{}' of type {:?} at position {:?}.",
+                    value_type, variable, target_type, token.position
+                ));
+            }
+            expression = AST::new(ASTNode::List(vec![expression]));
+        }
+
+        let value_type = self.infer_type(&expression)?;
+        self.expect_handled(&target_type, &operator, token.position)?;
+        self.expect_handled(&value_type, &operator, token.position)?;
+        let expression = AST::new(ASTNode::BinaryOperation {
+            operator: operator.clone(),
+            left: Box::new(target),
+            right: Box::new(expression),
+        });
+        if self.infer_type(&expression).is_err() {
+            return Err(format!(
+                "Type mismatch: cannot perform '{}' operation between {:?} and {:?} at position {:?}.",
+                operator, target_type, value_type, token.position
+            ));
+        }
+
+        Ok(AST::new(ASTNode::Assignment {
+            variable,
+            expression: Box::new(expression),
+        }))
+    }
+
+    /// Parses `DocComment+` followed by the declaration it documents.
+    fn parse_documented(&mut self) -> Result<AST, String> {
+        // `advance` has already dropped doc comments that document nothing.
+        let mut lines = Vec::new();
+        while self.current_token_is(TokenType::DocComment) {
+            lines.push(self.current_token.as_ref().unwrap().value.clone());
+            self.advance(); // Consume the doc comment
+        }
+
+        // The caller's statement node holds the doc comments and the declaration.
+        Ok(self.dispatch_statement()?.with_doc(lines.join("\n")))
+    }
+
     pub fn parse_statement(&mut self) -> Result<AST, String> {
+        let marker = self.mark();
+        let statement = self.dispatch_statement()?;
+        // Statements ending in 'end' or '}' take no ';', so drop one inserted after them.
+        self.skip_inserted_semicolon();
+        if let Some(kind) = NodeKind::of(&statement.node) {
+            self.finish_node(marker, kind);
+        }
+        Ok(statement)
+    }
+
+    /// The index of `current_token` in the token stream.
+    fn current_index(&self) -> usize {
+        self.tokens.consumed - usize::from(self.current_token.is_some())
+    }
+
+    /// Marks the current token as where a syntax node may start.
+    fn mark(&self) -> Marker {
+        Marker { event: self.events.len(), token: self.current_index() }
+    }
+
+    /// Records a node of `kind` from `marker` to the last token consumed, enclosing the
+    /// nodes recorded since.
+    fn finish_node(&mut self, marker: Marker, kind: NodeKind) {
+        self.events.insert(marker.event, Event::Start { kind, token: marker.token });
+        self.events.push(Event::Finish { token: self.consumed_end });
+    }
+
+    /// Runs `parse` inside a node of `kind`.
+    fn node<T>(&mut self, kind: NodeKind, parse: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        let marker = self.mark();
+        let result = parse(self)?;
+        self.finish_node(marker, kind);
+        Ok(result)
+    }
+
+    /// Consumes a `;` that the lexer inserted at a newline, if that is the current token.
+    fn skip_inserted_semicolon(&mut self) {
+        if self.current_token.as_ref().is_some_and(|token| token.token_type == TokenType::Termination && token.value != ";") {
+            self.advance();
+        }
+    }
+
+    fn dispatch_statement(&mut self) -> Result<AST, String> {
         match self.current_token {
             Some(ref token) if token.token_type == TokenType::Let => self.parse_let_decl(),
-            Some(ref token) if token.token_type == TokenType::Print => self.parse_print(),
+            Some(ref token) if token.token_type == TokenType::Const => self.parse_const_decl(),
+            Some(ref token) if token.token_type == TokenType::Print
+                || token.token_type == TokenType::PrintInline
+                || token.token_type == TokenType::PrintError => self.parse_print(),
             Some(ref token) if token.token_type == TokenType::If => self.parse_if_statement(),
             Some(ref token) if token.token_type == TokenType::Loop => self.parse_loop(),
             Some(ref token) if token.token_type == TokenType::Break => self.parse_break(),
+            Some(ref token) if token.token_type == TokenType::WriteFile => self.parse_write_file(),
+            Some(ref token) if token.token_type == TokenType::Struct => self.parse_struct_decl(),
+            Some(ref token) if token.token_type == TokenType::Enum => self.parse_enum_decl(),
+            Some(ref token) if token.token_type == TokenType::Try => self.parse_try(),
+            Some(ref token) if token.token_type == TokenType::Throw => self.parse_throw(),
+            Some(ref token) if token.token_type == TokenType::Assert
+                || token.token_type == TokenType::AssertEq => self.parse_assert(),
+            Some(ref token) if token.token_type == TokenType::Test => self.parse_test_block(),
+            Some(ref token) if token.token_type == TokenType::DocComment => self.parse_documented(),
             Some(ref token) if token.token_type == TokenType::Identifier => {
                 let identifier = token.value.clone();
+                let position = token.position;
                 self.advance();
                 if self.current_token_is(TokenType::Assign) {
+                    self.expect_mutable(&identifier, "assign to", position)?;
                     self.parse_assignment(identifier)
+                } else if self.current_token_is(TokenType::CompoundAssign) {
+                    self.expect_mutable(&identifier, "assign to", position)?;
+                    self.parse_compound_assignment(identifier)
+                } else if self.current_token_is(TokenType::LeftBracket) {
+                    self.expect_mutable(&identifier, "modify", position)?;
+                    self.parse_index_assignment(identifier)
                 } else if self.current_token_is(TokenType::Dot) {
                     self.advance();
                     if self.current_token_is(TokenType::Push) {
+                        self.expect_mutable(&identifier, "modify", position)?;
                         self.parse_push(identifier)
                     } else if self.current_token_is(TokenType::Pop) {
+                        self.expect_mutable(&identifier, "modify", position)?;
                         self.parse_pop(identifier)
+                    } else if self.current_token_is(TokenType::Identifier)
+                        && matches!(self.infer_type(&AST::new(ASTNode::Identifier(identifier.clone()))), Ok(SymbolType::Struct { .. })) {
+                        self.expect_mutable(&identifier, "modify", position)?;
+                        self.parse_field_assignment(identifier)
                     } else if self.current_token_is(TokenType::Identifier) {
                         let method_name = self.current_token.as_ref().unwrap().value.clone();
                         self.advance(); // Consume the method name
@@ -85,8 +338,16 @@ impl Parser {
                         match method_name.as_str() {
                             "fetch" => self.parse_fetch(AST::new(ASTNode::Identifier(identifier))),
                             "len" => self.parse_len(AST::new(ASTNode::Identifier(identifier))),
+                            "remove" => {
+                                let remove = self.parse_map_method(AST::new(ASTNode::Identifier(identifier)), "remove")?;
+                                if !self.current_token_is(TokenType::Termination) {
+                                    return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+                                }
+                                self.advance(); // Consume ';'
+                                Ok(remove)
+                            },
                             _ => Err(format!(
-                                "Unexpected method '{}' at position {:?}. Expected 'fetch' or 'len'.",
+                                "Unexpected method '{}' at position {:?}. Expected 'fetch', 'len' or 'remove'.",
                                 method_name, self.position
                             )),
                         }
@@ -98,13 +359,13 @@ impl Parser {
                     }
                 } else {
                     Err(format!(
-                        "Unexpected token {:?} at position {:?}. Expected ':=' for assignment or '.' for method call.",
+                        "Unexpected token {:?} at position {:?}. Expected ':=' or a compound assignment for assignment, '[' for index assignment, or '.' for method call.",
                         self.current_token, self.position
                     ))
                 }
             }
             _ => Err(format!(
-                "Unexpected token {:?} at position {:?}. Expected 'let', 'stdout', 'if', 'loop', 'break', or an identifier.",
+                "Unexpected token {:?} at position {:?}. Expected 'let', 'const', 'struct', 'enum', 'stdout', 'print', 'stderr', 'write_file', 'if', 'loop', 'break', 'try', 'throw', 'assert', 'assert_eq', 'test', or an identifier.",
                 self.current_token, self.position
             )),
         }
@@ -115,20 +376,20 @@ impl Parser {
 
             if self.current_token_is(TokenType::RightParen) {
                 self.advance(); // Consume ')'
-                return Ok(AST::new(ASTNode::Len {
+                Ok(AST::new(ASTNode::Len {
                     list: Box::new(list_expression),
-                }));
+                }))
             } else {
-                return Err(format!(
+                Err(format!(
                     "Expected ')' after 'len' at position {:?}. Found {:?}",
                     self.position, self.current_token
-                ));
+                ))
             }
         } else {
-            return Err(format!(
+            Err(format!(
                 "Expected '(' after 'len' at position {:?}. Found {:?}",
                 self.position, self.current_token
-            ));
+            ))
         }
     }
 
@@ -190,24 +451,243 @@ impl Parser {
             return Err(format!("Expected '{{' after 'loop' at position {:?}. Found {:?}", self.position, self.current_token));
         }
     
+        let block = self.mark();
         self.advance(); // Consume '{'
     
+        self.symbol_table.enter_scope();
         let mut body_statements = Vec::new();
         while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::EOF) {
             let statement = self.parse_statement()?;
             body_statements.push(statement);
         }
+        self.symbol_table.exit_scope();
     
         if !self.current_token_is(TokenType::RightBrace) {
             return Err(format!("Expected '}}' at position {:?}. Found {:?}", self.position, self.current_token));
         }
     
         self.advance(); // Consume '}'
+        self.finish_node(block, NodeKind::Block);
     
         Ok(AST::new(ASTNode::Loop {
             body: Box::new(AST::new(ASTNode::List(body_statements))),
         }))
     }
+    /// Parses `'try' '{' Statement* '}' 'catch' '(' Identifier ')' '{' Statement* '}'`.
+    pub fn parse_try(&mut self) -> Result<AST, String> {
+        self.advance(); // Consume 'try'
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after 'try' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let block = self.mark();
+        self.advance(); // Consume '{'
+
+        self.symbol_table.enter_scope();
+        let mut body = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::EOF) {
+            let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+            body.push((self.parse_statement()?, position));
+        }
+        self.symbol_table.exit_scope();
+
+        if !self.current_token_is(TokenType::RightBrace) {
+            return Err(format!("Expected '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '}'
+        self.finish_node(block, NodeKind::Block);
+        self.skip_inserted_semicolon(); // `catch` may start the next line
+
+        if !self.current_token_is(TokenType::Catch) {
+            return Err(format!("Expected 'catch' after 'try' block at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let catch = self.mark();
+
+        self.advance(); // Consume 'catch'
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after 'catch' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected identifier at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let variable = self.current_token.as_ref().unwrap().value.clone();
+        let declared_at = self.current_token.as_ref().unwrap().position;
+        self.advance(); // Consume the identifier
+
+        if !self.current_token_is(TokenType::RightParen) {
+            return Err(format!("Expected ')' after catch variable at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ')'
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after 'catch' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let block = self.mark();
+        self.advance(); // Consume '{'
+
+        // The catch variable is scoped to the handler.
+        self.symbol_table.enter_scope();
+        self.expect_undeclared(&variable, declared_at)?;
+        self.symbol_table.declare(variable.clone(), Symbol {
+            symbol_type: SymbolType::Error,
+            value: SymbolType::Error.default_value(),
+            mutability: Mutability::Immutable,
+            declared_at: Some(declared_at),
+        })?;
+        let mut handler = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::EOF) {
+            handler.push(self.parse_statement()?);
+        }
+        self.symbol_table.exit_scope();
+
+        if !self.current_token_is(TokenType::RightBrace) {
+            return Err(format!("Expected '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '}'
+        self.finish_node(block, NodeKind::Block);
+        self.finish_node(catch, NodeKind::Catch);
+
+        Ok(AST::new(ASTNode::Try { body, variable, handler }))
+    }
+
+    /// Parses `'throw' Expression ';'` where the expression is a message or a caught error.
+    pub fn parse_throw(&mut self) -> Result<AST, String> {
+        let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+        self.advance(); // Consume 'throw'
+
+        let value = self.parse_expression()?;
+        let value_type = self.infer_type(&value)?;
+        if value_type != SymbolType::String && value_type != SymbolType::Error {
+            return Err(format!(
+                "Type mismatch: throw expects a String or an Error, found {:?} at position {:?}.",
+                value_type, self.position
+            ));
+        }
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        Ok(AST::new(ASTNode::Throw { value: Box::new(value), position }))
+    }
+
+    /// Parses `'assert' '(' Expression ')' ';'` or `'assert_eq' '(' Expression ',' Expression ')' ';'`.
+    pub fn parse_assert(&mut self) -> Result<AST, String> {
+        let token = self.current_token.clone().unwrap();
+        let name = token.value.clone();
+        let start = token.position;
+        self.advance(); // Consume 'assert' or 'assert_eq'
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after '{}' at position {:?}. Found {:?}", name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        let first = self.parse_expression()?;
+        let second = if token.token_type == TokenType::AssertEq {
+            if !self.current_token_is(TokenType::Comma) {
+                return Err(format!("Expected ',' in '{}' at position {:?}. Found {:?}", name, self.position, self.current_token));
+            }
+            self.advance(); // Consume ','
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        if !self.current_token_is(TokenType::RightParen) {
+            return Err(format!("Expected ')' after '{}' arguments at position {:?}. Found {:?}", name, self.position, self.current_token));
+        }
+
+        let end = self.current_token.as_ref().unwrap().position;
+        self.advance(); // Consume ')'
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        let first_type = self.infer_type(&first)?;
+        match second {
+            None => {
+                if first_type != SymbolType::Boolean {
+                    return Err(format!(
+                        "Type mismatch: 'assert' expects a condition of type Boolean, found {:?} at position {:?}.",
+                        first_type, start
+                    ));
+                }
+                Ok(AST::new(ASTNode::Assert { condition: Box::new(first), start, end }))
+            }
+            Some(second) => {
+                let second_type = self.infer_type(&second)?;
+                if first_type != second_type {
+                    return Err(format!(
+                        "Type mismatch: cannot compare {:?} and {:?} in 'assert_eq' at position {:?}.",
+                        first_type, second_type, start
+                    ));
+                }
+                Ok(AST::new(ASTNode::AssertEq { left: Box::new(first), right: Box::new(second), start, end }))
+            }
+        }
+    }
+
+    /// Parses `'test' String '{' Statement* '}'`.
+    pub fn parse_test_block(&mut self) -> Result<AST, String> {
+        self.advance(); // Consume 'test'
+
+        if !self.current_token_is(TokenType::StringLiteral) {
+            return Err(format!("Expected test name string after 'test' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let name = self.current_token.as_ref().unwrap().value.clone();
+        self.advance(); // Consume the name
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after test name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let block = self.mark();
+        self.advance(); // Consume '{'
+
+        self.symbol_table.enter_scope();
+        let mut body = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) && !self.current_token_is(TokenType::EOF) {
+            body.push(self.parse_statement()?);
+        }
+        self.symbol_table.exit_scope();
+
+        if !self.current_token_is(TokenType::RightBrace) {
+            return Err(format!("Expected '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '}'
+        self.finish_node(block, NodeKind::Block);
+
+        Ok(AST::new(ASTNode::Test { name, body }))
+    }
+
+    /// Parses an if branch, which is a block of its own.
+    fn parse_scoped_statement(&mut self) -> Result<AST, String> {
+        self.symbol_table.enter_scope();
+        let statement = self.node(NodeKind::Branch, Self::parse_statement);
+        self.symbol_table.exit_scope();
+        statement
+    }
+
     pub fn parse_if_statement(&mut self) -> Result<AST, String> {
         if !self.current_token_is(TokenType::If) {
             return Err(format!("Expected 'if' at position {:?}. Found {:?}", self.position, self.current_token));
@@ -221,7 +701,15 @@ impl Parser {
     
         self.advance(); // Consume '('
     
+        let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
         let condition = self.parse_expression()?;
+        let condition_type = self.infer_type(&condition)?;
+        if condition_type != SymbolType::Boolean {
+            return Err(format!(
+                "Type mismatch: 'if' condition must be of type Boolean, found {:?} at position {:?}.",
+                condition_type, position
+            ));
+        }
     
         if !self.current_token_is(TokenType::RightParen) {
             return Err(format!("Expected ')' after condition at position {:?}. Found {:?}", self.position, self.current_token));
@@ -235,12 +723,12 @@ impl Parser {
     
         self.advance(); // Consume 'then'
     
-        let then_branch = self.parse_statement()?;
+        let then_branch = self.parse_scoped_statement()?;
     
         let mut else_branch = None;
         if self.current_token_is(TokenType::Else) {
             self.advance(); // Consume 'else'
-            else_branch = Some(self.parse_statement()?);
+            else_branch = Some(self.parse_scoped_statement()?);
         }
     
         if !self.current_token_is(TokenType::End) {
@@ -284,57 +772,201 @@ impl Parser {
             list: Box::new(AST::new(ASTNode::Identifier(list_name))),
         }))
     }
-    pub fn parse_let_decl(&mut self) -> Result<AST, String> {
-        if !self.current_token_is(TokenType::Let) {
-            return Err(format!("Expected 'let' at position {:?}. Found {:?}", self.position, self.current_token));
+    /// A name can be declared once per block. Declarations in an inner block shadow outer
+    /// variables until the block ends, but never a constant.
+    fn expect_undeclared(&self, name: &str, position: Position) -> Result<(), String> {
+        if self.symbol_table.lookup_current(name).is_some() {
+            return Err(format!("Variable '{}' already declared at position {:?}.", name, position));
         }
-    
-        self.advance(); 
-        if !self.current_token_is(TokenType::Identifier) {
-            return Err(format!("Expected identifier at position {:?}. Found {:?}", self.position, self.current_token));
+        match self.symbol_table.lookup(name) {
+            Some(Symbol { mutability: Mutability::Constant, declared_at, .. }) => Err(format!(
+                "Cannot shadow constant '{}' at position {:?}; it is declared at position {:?}.",
+                name, position, declared_at.unwrap_or(position)
+            )),
+            _ => Ok(()),
         }
-    
-        let variable = self.current_token.as_ref().unwrap().value.clone();
-        if self.symbol_table.lookup(&variable).is_some() {
-            return Err(format!(
-                "Variable '{}' already declared at position {:?}.",
-                variable, self.position
-            ));
+    }
+
+    /// Fails unless `name` was declared with `let mut`, pointing at both the use and the declaration.
+    fn expect_mutable(&self, name: &str, action: &str, position: Position) -> Result<(), String> {
+        let Some(symbol) = self.symbol_table.lookup(name) else {
+            return Ok(()); // Reported as an undefined variable by the type checks that follow.
+        };
+        let kind = match symbol.mutability {
+            Mutability::Mutable => return Ok(()),
+            Mutability::Constant => "constant",
+            Mutability::Immutable => "immutable variable",
+        };
+        match symbol.declared_at {
+            Some(declared_at) => Err(format!(
+                "Cannot {} {} '{}' at position {:?}. '{}' is declared at position {:?}; use 'let mut' to make it mutable.",
+                action, kind, name, position, name, declared_at
+            )),
+            None => Err(format!("Cannot {} {} '{}' at position {:?}.", action, kind, name, position)),
         }
-    
-        self.advance(); 
-    
-        let symbol_type = match self.current_token {
-            Some(ref token) if token.token_type == TokenType::IntType => SymbolType::Int,
-            Some(ref token) if token.token_type == TokenType::FloatType => SymbolType::Float,
-            Some(ref token) if token.token_type == TokenType::BoolType => SymbolType::Boolean,
-            Some(ref token) if token.token_type == TokenType::ListIntType => SymbolType::List(Box::new(SymbolType::Int)),
-            Some(ref token) if token.token_type == TokenType::ListFloatType => SymbolType::List(Box::new(SymbolType::Float)),
-            Some(ref token) if token.token_type == TokenType::ListBoolType => SymbolType::List(Box::new(SymbolType::Boolean)),
-            Some(ref token) if token.token_type == TokenType::Colon => {
-                self.advance(); // Advance to the next token
-                match self.current_token {
-                    Some(ref token) if token.token_type == TokenType::IntType => SymbolType::List(Box::new(SymbolType::Int)),
-                    Some(ref token) if token.token_type == TokenType::FloatType => SymbolType::List(Box::new(SymbolType::Float)),
-                    Some(ref token) if token.token_type == TokenType::BoolType => SymbolType::List(Box::new(SymbolType::Boolean)),
-                    _ => return Err(format!(
-                        "Expected list type 'int', 'float', or 'bool' at position {:?}. Found {:?}.",
-                        self.position, self.current_token
-                    )),
+    }
+
+    /// Whether `ast` can be evaluated while parsing: literals, constants, and operators,
+    /// collections and accessors built only from those.
+    fn is_constant(&self, ast: &AST) -> bool {
+        match &ast.node {
+            ASTNode::Int(_) | ASTNode::Float(_) | ASTNode::Boolean(_) | ASTNode::String(_) | ASTNode::None => true,
+            ASTNode::Identifier(name) => self.symbol_table.lookup(name)
+                .is_some_and(|symbol| symbol.mutability == Mutability::Constant),
+            ASTNode::BinaryOperation { left, right, .. } => self.is_constant(left) && self.is_constant(right),
+            ASTNode::List(elements) | ASTNode::Tuple(elements) => elements.iter().all(|e| self.is_constant(e)),
+            ASTNode::Map(entries) => entries.iter().all(|(k, v)| self.is_constant(k) && self.is_constant(v)),
+            ASTNode::StructLiteral { fields, .. } => fields.iter().all(|(_, value)| self.is_constant(value)),
+            ASTNode::EnumVariant { values, .. } => values.iter().all(|value| self.is_constant(value)),
+            ASTNode::Format(segments) => segments.iter().all(|segment| match segment {
+                FormatSegment::Literal(_) => true,
+                FormatSegment::Placeholder { expression, .. } => self.is_constant(expression),
+            }),
+            ASTNode::Index { collection, index } => self.is_constant(collection) && self.is_constant(index),
+            ASTNode::TupleIndex { tuple, .. } => self.is_constant(tuple),
+            ASTNode::FieldAccess { object, .. } => self.is_constant(object),
+            _ => false,
+        }
+    }
+
+    /// Like `infer_type`, but an empty `[]` or `{}` takes the expected collection type.
+    fn infer_type_as(&self, ast: &AST, expected: &SymbolType) -> Result<SymbolType, String> {
+        if ast.is_empty_collection_of(expected) {
+            return Ok(expected.clone());
+        }
+        self.infer_type(ast)
+    }
+
+    /// The type of a declaration: the annotation, checked against the expression, or else the
+    /// type inferred from the expression.
+    fn declared_type(
+        &self,
+        name: &str,
+        kind: &str,
+        annotation: Option<SymbolType>,
+        expression: &AST,
+        position: Position,
+    ) -> Result<SymbolType, String> {
+        if let Some(symbol_type) = annotation {
+            let expr_type = self.infer_type_as(expression, &symbol_type)?;
+            if !symbol_type.accepts(&expr_type) {
+                return Err(format!(
+                    "Type mismatch: cannot assign expression of type {:?} to {} of type {:?} at position {:?}.",
+                    expr_type, kind, symbol_type, position
+                ));
+            }
+            return Ok(symbol_type);
+        }
+
+        // Only these literals say nothing about their type; other failures are errors in the expression.
+        let literal = match &expression.node {
+            ASTNode::List(elements) if elements.is_empty() => "[]",
+            ASTNode::Map(entries) if entries.is_empty() => "{}",
+            _ => {
+                let inferred = self.infer_type(expression)?;
+                if inferred != SymbolType::none_type() {
+                    return Ok(inferred);
                 }
-            },
-            _ => return Err(format!(
-                "Expected type 'int', 'float', 'bool', or list at position {:?}. Found {:?}.",
-                self.position, self.current_token
-            )),
+                "none"
+            }
         };
-    
-        self.advance(); 
-        if !self.current_token_is(TokenType::Assign) {
-            return Err(format!(
-                "Expected ':=' at position {:?}. Found {:?}",
-                self.position, self.current_token
-            ));
+        Err(format!(
+            "Cannot infer the type of '{}' from '{}' at position {:?}. Add a type annotation.",
+            name, literal, position
+        ))
+    }
+
+    /// Parses `'const' Identifier Type? ':=' Expression ';'`.
+    pub fn parse_const_decl(&mut self) -> Result<AST, String> {
+        self.advance(); // Consume 'const'
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected identifier at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let name = self.current_token.as_ref().unwrap().value.clone();
+        let declared_at = self.current_token.as_ref().unwrap().position;
+        self.expect_undeclared(&name, declared_at)?;
+
+        self.advance(); // Consume the name
+
+        let annotation = if self.current_token_is(TokenType::Assign) {
+            None
+        } else {
+            Some(self.parse_annotation()?)
+        };
+
+        if !self.current_token_is(TokenType::Assign) {
+            return Err(format!("Expected ':=' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ':='
+
+        let expression = self.parse_expression()?;
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        let symbol_type = self.declared_type(&name, "constant", annotation, &expression, declared_at)?;
+        if !self.is_constant(&expression) {
+            return Err(format!(
+                "Constant '{}' must be initialized with a constant expression at position {:?}.",
+                name, declared_at
+            ));
+        }
+
+        let value = self.evaluate_expression(&expression)
+            .map_err(|e| format!("Cannot evaluate constant '{}' at position {:?}: {}", name, declared_at, e))?;
+
+        self.symbol_table.declare(name.clone(), Symbol {
+            symbol_type: symbol_type.clone(),
+            value: value.clone(),
+            mutability: Mutability::Constant,
+            declared_at: Some(declared_at),
+        })?;
+
+        Ok(AST::new(ASTNode::ConstDecl { name, symbol_type, value }))
+    }
+
+    pub fn parse_let_decl(&mut self) -> Result<AST, String> {
+        if !self.current_token_is(TokenType::Let) {
+            return Err(format!("Expected 'let' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+    
+        self.advance(); 
+        let mutability = if self.current_token_is(TokenType::Mut) {
+            self.advance(); // Consume 'mut'
+            Mutability::Mutable
+        } else {
+            Mutability::Immutable
+        };
+        if self.current_token_is(TokenType::LeftParen) {
+            return self.parse_destructuring_let(mutability);
+        }
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected identifier at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+    
+        let variable = self.current_token.as_ref().unwrap().value.clone();
+        let declared_at = self.current_token.as_ref().unwrap().position;
+        self.expect_undeclared(&variable, declared_at)?;
+    
+        self.advance(); 
+    
+        let annotation = if self.current_token_is(TokenType::Assign) {
+            None
+        } else {
+            Some(self.parse_annotation()?)
+        };
+    
+        if !self.current_token_is(TokenType::Assign) {
+            return Err(format!(
+                "Expected ':=' at position {:?}. Found {:?}",
+                self.position, self.current_token
+            ));
         }
     
         self.advance();
@@ -369,34 +1001,890 @@ impl Parser {
                 ));
             }
         }
-    
+    
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!(
+                "Expected ';' at position {:?}. Found {:?}",
+                self.position, self.current_token
+            ));
+        }
+    
+        self.advance();
+    
+        let symbol_type = self.declared_type(&variable, "variable", annotation, &expression, declared_at)?;
+    
+        // Statements before this one have not run yet (a key inserted into a map, an element
+        // pushed onto a list), so a failed evaluation only means the value is unknown here.
+        // The interpreter assigns the real value when it executes the declaration.
+        let value = self.evaluate_expression(&expression)
+            .unwrap_or_else(|_| symbol_type.default_value());
+    
+        let symbol = Symbol { symbol_type: symbol_type.clone(), value, mutability, declared_at: Some(declared_at) };
+        self.symbol_table.declare(variable.clone(), symbol)
+            .map_err(|e| format!("Error inserting symbol into symbol table: {}", e))?;
+    
+        Ok(AST::new(ASTNode::Let {
+            variable,
+            symbol_type,
+            mutability,
+            expression: Box::new(expression),
+        }))
+    }
+    /// Parses `'(' Identifier (':' Type)? (',' Identifier (':' Type)?)* ')' ':=' Expression ';'` after 'let' or 'let mut'.
+    fn parse_destructuring_let(&mut self, mutability: Mutability) -> Result<AST, String> {
+        let declared_at = self.current_token.as_ref().unwrap().position;
+        self.advance(); // Consume '('
+
+        let mut variables = Vec::new();
+        while !self.current_token_is(TokenType::RightParen) {
+            if !self.current_token_is(TokenType::Identifier) {
+                return Err(format!("Expected identifier at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            let variable = self.current_token.as_ref().unwrap().value.clone();
+            let position = self.current_token.as_ref().unwrap().position;
+            self.expect_undeclared(&variable, position)?;
+            if variables.iter().any(|(name, _)| *name == variable) {
+                return Err(format!(
+                    "Variable '{}' already declared at position {:?}.",
+                    variable, position
+                ));
+            }
+
+            self.advance(); // Consume the identifier
+
+            let annotation = if self.current_token_is(TokenType::Comma) || self.current_token_is(TokenType::RightParen) {
+                None
+            } else {
+                Some(self.parse_annotation()?)
+            };
+            variables.push((variable, annotation));
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightParen) {
+                return Err(format!("Expected ',' or ')' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume ')'
+
+        if !self.current_token_is(TokenType::Assign) {
+            return Err(format!("Expected ':=' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ':='
+
+        let expression = self.parse_expression()?;
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        let element_types = match self.infer_type(&expression)? {
+            SymbolType::Tuple(element_types) => element_types,
+            other => return Err(format!(
+                "Type mismatch: cannot destructure expression of type {:?} at position {:?}. Expected a tuple.",
+                other, self.position
+            )),
+        };
+        if element_types.len() != variables.len() {
+            return Err(format!(
+                "Type mismatch: cannot destructure a tuple of {} elements into {} variables at position {:?}.",
+                element_types.len(), variables.len(), self.position
+            ));
+        }
+        for ((variable, annotation), element_type) in variables.iter().zip(&element_types) {
+            if let Some(annotation) = annotation {
+                if !annotation.accepts(element_type) {
+                    return Err(format!(
+                        "Type mismatch: cannot assign element of type {:?} to variable '{}' of type {:?} at position {:?}.",
+                        element_type, variable, annotation, self.position
+                    ));
+                }
+            }
+        }
+
+        let values = match self.evaluate_expression(&expression) {
+            Ok(SymbolValue::Tuple(values)) => values,
+            _ => element_types.iter().map(|t| t.default_value()).collect(),
+        };
+        for (((variable, _), element_type), value) in variables.iter().zip(&element_types).zip(values) {
+            let symbol = Symbol { symbol_type: element_type.clone(), value, mutability, declared_at: Some(declared_at) };
+            self.symbol_table.declare(variable.clone(), symbol)
+                .map_err(|e| format!("Error inserting symbol into symbol table: {}", e))?;
+        }
+
+        Ok(AST::new(ASTNode::Destructure {
+            variables: variables.into_iter().map(|(name, _)| name).zip(element_types).collect(),
+            mutability,
+            expression: Box::new(expression),
+        }))
+    }
+
+    pub fn parse_struct_decl(&mut self) -> Result<AST, String> {
+        self.advance(); // Consume 'struct'
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected struct name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let name = self.current_token.as_ref().unwrap().value.clone();
+        let position = self.current_token.as_ref().unwrap().position;
+        if self.structs.contains_key(&name) || self.enums.contains_key(&name) {
+            return Err(format!("Type '{}' already declared at position {:?}.", name, position));
+        }
+
+        self.advance(); // Consume the name
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after struct name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '{'
+
+        let mut fields: Vec<(String, SymbolType)> = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) {
+            if !self.current_token_is(TokenType::Identifier) {
+                return Err(format!("Expected field name at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            let field = self.current_token.as_ref().unwrap().value.clone();
+            if fields.iter().any(|(existing, _)| *existing == field) {
+                let position = self.current_token.as_ref().unwrap().position;
+                return Err(format!("Field '{}' already declared in struct '{}' at position {:?}.", field, name, position));
+            }
+
+            self.advance(); // Consume the field name
+            fields.push((field, self.parse_annotation()?));
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightBrace) {
+                return Err(format!("Expected ',' or '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume '}'
+
+        self.structs.insert(name.clone(), SymbolType::Struct { name: name.clone(), fields: fields.clone() });
+
+        Ok(AST::new(ASTNode::StructDecl { name, fields }))
+    }
+
+    pub fn parse_enum_decl(&mut self) -> Result<AST, String> {
+        self.advance(); // Consume 'enum'
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected enum name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let name = self.current_token.as_ref().unwrap().value.clone();
+        let position = self.current_token.as_ref().unwrap().position;
+        if self.structs.contains_key(&name) || self.enums.contains_key(&name) {
+            return Err(format!("Type '{}' already declared at position {:?}.", name, position));
+        }
+
+        self.advance(); // Consume the name
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after enum name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '{'
+
+        let mut variants: Vec<(String, Vec<SymbolType>)> = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) {
+            if !self.current_token_is(TokenType::Identifier) {
+                return Err(format!("Expected variant name at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            let variant = self.current_token.as_ref().unwrap().value.clone();
+            if variants.iter().any(|(existing, _)| *existing == variant) {
+                let position = self.current_token.as_ref().unwrap().position;
+                return Err(format!("Variant '{}' already declared in enum '{}' at position {:?}.", variant, name, position));
+            }
+
+            self.advance(); // Consume the variant name
+
+            let mut payload = Vec::new();
+            if self.current_token_is(TokenType::LeftParen) {
+                self.advance(); // Consume '('
+                payload.push(self.parse_type()?);
+                while self.current_token_is(TokenType::Comma) {
+                    self.advance(); // Consume ','
+                    payload.push(self.parse_type()?);
+                }
+                if !self.current_token_is(TokenType::RightParen) {
+                    return Err(format!("Expected ',' or ')' in variant payload at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+                self.advance(); // Consume ')'
+            }
+            variants.push((variant, payload));
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightBrace) {
+                return Err(format!("Expected ',' or '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume '}'
+
+        if variants.is_empty() {
+            return Err(format!("Enum '{}' must declare at least one variant at position {:?}.", name, position));
+        }
+
+        self.enums.insert(name.clone(), SymbolType::Enum { name: name.clone(), variants: variants.clone() });
+
+        Ok(AST::new(ASTNode::EnumDecl { name, variants }))
+    }
+
+    /// Parses `'.' Identifier ('(' Expression (',' Expression)* ')')?` after an enum name.
+    fn parse_enum_variant(&mut self, name: String) -> Result<AST, String> {
+        let dot_position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+        let enum_type = self.enums.get(&name).cloned()
+            .ok_or_else(|| format!("Unknown enum '{}' at position {:?}.", name, dot_position))?;
+        let SymbolType::Enum { variants, .. } = &enum_type else {
+            return Err(format!("Unknown enum '{}' at position {:?}.", name, dot_position));
+        };
+
+        self.advance(); // Consume '.'
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected variant name after '{}.' at position {:?}. Found {:?}", name, self.position, self.current_token));
+        }
+
+        let variant = self.current_token.as_ref().unwrap().value.clone();
+        let position = self.current_token.as_ref().unwrap().position;
+        let payload = match variants.iter().find(|(declared, _)| *declared == variant) {
+            Some((_, payload)) => payload.clone(),
+            None => return Err(format!("Enum '{}' has no variant '{}' at position {:?}.", name, variant, position)),
+        };
+
+        self.advance(); // Consume the variant name
+
+        let mut values = Vec::new();
+        if self.current_token_is(TokenType::LeftParen) {
+            self.advance(); // Consume '('
+            while !self.current_token_is(TokenType::RightParen) {
+                values.push(self.parse_expression()?);
+                if self.current_token_is(TokenType::Comma) {
+                    self.advance(); // Consume ','
+                } else if !self.current_token_is(TokenType::RightParen) {
+                    return Err(format!("Expected ',' or ')' at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+            }
+            self.advance(); // Consume ')'
+        }
+
+        if values.len() != payload.len() {
+            return Err(format!(
+                "Variant '{}.{}' expects {} value(s), found {} at position {:?}.",
+                name, variant, payload.len(), values.len(), position
+            ));
+        }
+        for (value, expected) in values.iter().zip(&payload) {
+            let value_type = self.infer_type_as(value, expected)?;
+            if !expected.accepts(&value_type) {
+                return Err(format!(
+                    "Type mismatch: variant '{}.{}' expects a value of type {:?}, found {:?} at position {:?}.",
+                    name, variant, expected, value_type, position
+                ));
+            }
+        }
+
+        Ok(AST::new(ASTNode::EnumVariant { enum_type, variant, values }))
+    }
+
+    /// Parses `'match' Expression '{' (Pattern '=>' Expression (',' Pattern '=>' Expression)*)? '}'`.
+    /// Every variant of the scrutinee's enum must be covered by an arm or by a `_` wildcard.
+    fn parse_match(&mut self) -> Result<AST, String> {
+        let match_position = self.current_token.as_ref().unwrap().position;
+        self.advance(); // Consume 'match'
+
+        let scrutinee_position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+        let scrutinee = self.parse_expression()?;
+        let (name, variants) = match self.infer_type(&scrutinee)? {
+            SymbolType::Enum { name, variants } => (name, variants),
+            other => return Err(format!(
+                "Type mismatch: match can only be performed on enums, found {:?} at position {:?}.",
+                other, scrutinee_position
+            )),
+        };
+
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' after match expression at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '{'
+
+        let mut arms: Vec<MatchArm> = Vec::new();
+        let mut result_type: Option<SymbolType> = None;
+        while !self.current_token_is(TokenType::RightBrace) {
+            let arm = self.mark();
+            let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+            let pattern = self.parse_pattern(&name, &variants)?;
+            let reachable = match &pattern {
+                Pattern::Variant { variant, .. } => !arms.iter().any(|arm| arm.pattern.matches(variant)),
+                Pattern::Wildcard => !arms.iter().any(|arm| arm.pattern == Pattern::Wildcard),
+            };
+            if !reachable {
+                return Err(format!("Unreachable match arm '{}' at position {:?}.", pattern, position));
+            }
+
+            if !self.current_token_is(TokenType::FatArrow) {
+                return Err(format!("Expected '=>' after pattern at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            self.advance(); // Consume '=>'
+
+            // Bindings only exist inside their arm.
+            self.symbol_table.enter_scope();
+            let body = self.parse_match_arm_body(&pattern, position);
+            self.symbol_table.exit_scope();
+            let (body, body_type) = body?;
+
+            match &result_type {
+                Some(expected) if *expected != body_type => {
+                    return Err(format!(
+                        "Type mismatch: match arm '{}' has type {:?}, expected {:?} at position {:?}.",
+                        pattern, body_type, expected, position
+                    ));
+                }
+                Some(_) => {}
+                None => result_type = Some(body_type),
+            }
+            arms.push(MatchArm { pattern, body });
+            self.finish_node(arm, NodeKind::MatchArm);
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightBrace) {
+                return Err(format!("Expected ',' or '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume '}'
+
+        let missing: Vec<&str> = variants.iter()
+            .map(|(variant, _)| variant.as_str())
+            .filter(|variant| !arms.iter().any(|arm| arm.pattern.matches(variant)))
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Non-exhaustive match on '{}': missing variant(s) {} at position {:?}.",
+                name, missing.join(", "), match_position
+            ));
+        }
+
+        Ok(AST::new(ASTNode::Match {
+            scrutinee: Box::new(scrutinee),
+            arms,
+            result_type: result_type.unwrap_or(SymbolType::Void),
+        }))
+    }
+
+    /// Parses `'_'` or `Identifier '.' Identifier ('(' Identifier (',' Identifier)* ')')?`.
+    fn parse_pattern(&mut self, name: &str, variants: &[(String, Vec<SymbolType>)]) -> Result<Pattern, String> {
+        let (enum_name, position) = match self.current_token {
+            Some(ref token) if token.token_type == TokenType::Identifier => (token.value.clone(), token.position),
+            _ => return Err(format!("Expected pattern at position {:?}. Found {:?}", self.position, self.current_token)),
+        };
+
+        self.advance(); // Consume the enum name or '_'
+
+        if enum_name == "_" {
+            return Ok(Pattern::Wildcard);
+        }
+        if enum_name != name {
+            return Err(format!(
+                "Type mismatch: pattern of enum '{}' cannot match a value of enum '{}' at position {:?}.",
+                enum_name, name, position
+            ));
+        }
+
+        if !self.current_token_is(TokenType::Dot) {
+            return Err(format!("Expected '.' after '{}' in pattern at position {:?}. Found {:?}", name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '.'
+
+        if !self.current_token_is(TokenType::Identifier) {
+            return Err(format!("Expected variant name at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        let variant = self.current_token.as_ref().unwrap().value.clone();
+        let payload = match variants.iter().find(|(declared, _)| *declared == variant) {
+            Some((_, payload)) => payload,
+            None => {
+                let position = self.current_token.as_ref().unwrap().position;
+                return Err(format!("Enum '{}' has no variant '{}' at position {:?}.", name, variant, position));
+            }
+        };
+
+        self.advance(); // Consume the variant name
+
+        let mut names = Vec::new();
+        if self.current_token_is(TokenType::LeftParen) {
+            self.advance(); // Consume '('
+            while !self.current_token_is(TokenType::RightParen) {
+                if !self.current_token_is(TokenType::Identifier) {
+                    return Err(format!("Expected binding name at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+                names.push(self.current_token.as_ref().unwrap().value.clone());
+                self.advance(); // Consume the binding
+
+                if self.current_token_is(TokenType::Comma) {
+                    self.advance(); // Consume ','
+                } else if !self.current_token_is(TokenType::RightParen) {
+                    return Err(format!("Expected ',' or ')' at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+            }
+            self.advance(); // Consume ')'
+        }
+
+        if names.len() != payload.len() {
+            return Err(format!(
+                "Pattern '{}.{}' expects {} binding(s), found {} at position {:?}.",
+                name, variant, payload.len(), names.len(), position
+            ));
+        }
+
+        Ok(Pattern::Variant { variant, bindings: names.into_iter().zip(payload.iter().cloned()).collect() })
+    }
+
+    /// Declares the pattern's bindings in the current scope, then parses and types the arm body.
+    /// `position` is where the pattern starts.
+    fn parse_match_arm_body(&mut self, pattern: &Pattern, position: Position) -> Result<(AST, SymbolType), String> {
+        if let Pattern::Variant { bindings, .. } = pattern {
+            for (binding, binding_type) in bindings.iter().filter(|(binding, _)| binding != "_") {
+                self.expect_undeclared(binding, position)?;
+                self.symbol_table.insert(binding.clone(), binding_type.clone(), binding_type.default_value())?;
+            }
+        }
+
+        let body = self.parse_expression()?;
+        let body_type = self.infer_type(&body)?;
+        Ok((body, body_type))
+    }
+
+    /// Parses `'{' (Identifier ':' Expression (',' Identifier ':' Expression)*)? '}'` after a struct name.
+    fn parse_struct_literal(&mut self, name: String) -> Result<AST, String> {
+        let declared_fields = match self.structs.get(&name) {
+            Some(SymbolType::Struct { fields, .. }) => fields.clone(),
+            _ => return Err(format!("Unknown struct '{}' at position {:?}.", name, self.position)),
+        };
+
+        self.advance(); // Consume '{'
+
+        let mut values: Vec<(String, AST)> = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) {
+            if !self.current_token_is(TokenType::Identifier) {
+                return Err(format!("Expected field name at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            let field = self.current_token.as_ref().unwrap().value.clone();
+            let field_type = match declared_fields.iter().find(|(declared, _)| *declared == field) {
+                Some((_, field_type)) => field_type.clone(),
+                None => return Err(format!("Struct '{}' has no field '{}' at position {:?}.", name, field, self.position)),
+            };
+            if values.iter().any(|(existing, _)| *existing == field) {
+                return Err(format!("Field '{}' specified more than once at position {:?}.", field, self.position));
+            }
+
+            self.advance(); // Consume the field name
+
+            if !self.current_token_is(TokenType::Colon) {
+                return Err(format!("Expected ':' after field name at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            self.advance(); // Consume ':'
+
+            let value = self.parse_expression()?;
+            let value_type = self.infer_type_as(&value, &field_type)?;
+            if !field_type.accepts(&value_type) {
+                return Err(format!(
+                    "Type mismatch: cannot assign expression of type {:?} to field '{}' of type {:?} at position {:?}.",
+                    value_type, field, field_type, self.position
+                ));
+            }
+            values.push((field, value));
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightBrace) {
+                return Err(format!("Expected ',' or '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume '}'
+
+        let missing: Vec<&str> = declared_fields.iter()
+            .filter(|(declared, _)| !values.iter().any(|(field, _)| field == declared))
+            .map(|(declared, _)| declared.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(format!(
+                "Missing field(s) {} in '{}' literal at position {:?}.",
+                missing.join(", "), name, self.position
+            ));
+        }
+
+        // Keep fields in declaration order so values and types line up.
+        let fields = declared_fields.iter()
+            .map(|(declared, _)| values.iter().find(|(field, _)| field == declared).unwrap().clone())
+            .collect();
+
+        Ok(AST::new(ASTNode::StructLiteral { name, fields }))
+    }
+
+    /// Parses `('.' Identifier)+ ':=' Expression ';'` once the variable and its first '.' are consumed.
+    fn parse_field_assignment(&mut self, variable: String) -> Result<AST, String> {
+        let mut fields = Vec::new();
+        let mut target = AST::new(ASTNode::Identifier(variable.clone()));
+        loop {
+            if !self.current_token_is(TokenType::Identifier) {
+                return Err(format!("Expected field name at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            let field = self.current_token.as_ref().unwrap().value.clone();
+            self.advance(); // Consume the field name
+
+            target = AST::new(ASTNode::FieldAccess { object: Box::new(target), field: field.clone() });
+            self.infer_type(&target)?;
+            fields.push(field);
+
+            if !self.current_token_is(TokenType::Dot) {
+                break;
+            }
+            self.advance(); // Consume '.'
+        }
+
+        if !self.current_token_is(TokenType::Assign) {
+            return Err(format!("Expected ':=' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ':='
+
+        let expression = self.parse_expression()?;
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ';'
+
+        let target_type = self.infer_type(&target)?;
+        let expression_type = self.infer_type_as(&expression, &target_type)?;
+        if !target_type.accepts(&expression_type) {
+            return Err(format!(
+                "Type mismatch: cannot assign expression of type {:?} to field '{}' of type {:?} at position {:?}.",
+                expression_type, fields.join("."), target_type, self.position
+            ));
+        }
+
+        Ok(AST::new(ASTNode::FieldAssignment {
+            variable,
+            fields,
+            expression: Box::new(expression),
+        }))
+    }
+
+    /// Parses the `':' Type` after a declared name.
+    fn parse_annotation(&mut self) -> Result<SymbolType, String> {
+        if !self.current_token_is(TokenType::Colon) {
+            return Err(format!("Expected ':' before type at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+        self.advance(); // Consume ':'
+        self.node(NodeKind::Type, Self::parse_type)
+    }
+
+    /// Parses a type followed by any number of `?` markers, e.g. `int?`.
+    pub fn parse_type(&mut self) -> Result<SymbolType, String> {
+        let symbol_type = self.parse_base_type()?;
+        if !self.current_token_is(TokenType::Question) {
+            return Ok(symbol_type);
+        }
+
+        self.advance(); // Consume '?'
+        if self.current_token_is(TokenType::Question) {
+            return Err(format!("Nested optional types are not supported at position {:?}.", self.position));
+        }
+        Self::optional_of(symbol_type, self.position)
+    }
+
+    fn optional_of(symbol_type: SymbolType, position: Position) -> Result<SymbolType, String> {
+        match symbol_type {
+            SymbolType::Option(_) => Err(format!("Nested optional types are not supported at position {:?}.", position)),
+            inner => Ok(SymbolType::Option(Box::new(inner))),
+        }
+    }
+
+    /// Parses `'option' '<' Type '>'` once the current token is 'option'.
+    fn parse_option_type(&mut self) -> Result<SymbolType, String> {
+        self.advance(); // Consume 'option'
+
+        if !self.current_token_is(TokenType::LessThan) {
+            return Err(format!("Expected '<' after 'option' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '<'
+        let inner = self.parse_type()?;
+
+        if !self.current_token_is(TokenType::GreaterThan) {
+            return Err(format!("Expected '>' after option type at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '>'
+        Self::optional_of(inner, self.position)
+    }
+
+    fn parse_base_type(&mut self) -> Result<SymbolType, String> {
+        let symbol_type = match self.current_token {
+            // Type names are ordinary identifiers, so they may also name variables.
+            Some(ref token) if token.token_type == TokenType::Identifier => match token.value.as_str() {
+                "int" => SymbolType::Int,
+                "float" => SymbolType::Float,
+                "bool" => SymbolType::Boolean,
+                "string" => SymbolType::String,
+                "error" => SymbolType::Error,
+                "option" => return self.parse_option_type(),
+                name => match self.structs.get(name).or_else(|| self.enums.get(name)) {
+                    Some(declared_type) => declared_type.clone(),
+                    None => return Err(format!("Unknown type '{}' at position {:?}.", name, self.position)),
+                },
+            },
+            Some(ref token) if token.token_type == TokenType::LeftBracket => {
+                self.advance(); // Consume '['
+                let element_type = self.parse_type()?;
+                if !self.current_token_is(TokenType::RightBracket) {
+                    return Err(format!("Expected ']' after list element type at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+                SymbolType::List(Box::new(element_type))
+            },
+            Some(ref token) if token.token_type == TokenType::LeftBrace => return self.parse_map_type(),
+            Some(ref token) if token.token_type == TokenType::LeftParen => {
+                self.advance(); // Consume '('
+                let mut element_types = vec![self.parse_type()?];
+                while self.current_token_is(TokenType::Comma) {
+                    self.advance(); // Consume ','
+                    element_types.push(self.parse_type()?);
+                }
+                if !self.current_token_is(TokenType::RightParen) {
+                    return Err(format!("Expected ',' or ')' in tuple type at position {:?}. Found {:?}", self.position, self.current_token));
+                }
+                self.advance(); // Consume ')'
+                return Ok(if element_types.len() == 1 {
+                    element_types.remove(0)
+                } else {
+                    SymbolType::Tuple(element_types)
+                });
+            },
+            _ => return Err(format!(
+                "Expected type 'int', 'float', 'bool', 'string', list, map, or tuple at position {:?}. Found {:?}.",
+                self.position, self.current_token
+            )),
+        };
+
+        self.advance(); // Consume the type
+        Ok(symbol_type)
+    }
+
+    fn parse_map_type(&mut self) -> Result<SymbolType, String> {
+        self.advance(); // Consume '{'
+
+        let key_type = self.parse_type()?;
+        if !key_type.is_map_key() {
+            return Err(format!(
+                "Map keys must be of type Int, Boolean, or String, found {:?} at position {:?}.",
+                key_type, self.position
+            ));
+        }
+
+        if !self.current_token_is(TokenType::Colon) {
+            return Err(format!("Expected ':' after map key type at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+        self.advance(); // Consume ':'
+
+        let value_type = self.parse_type()?;
+
+        if !self.current_token_is(TokenType::RightBrace) {
+            return Err(format!("Expected '}}' after map value type at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '}'
+
+        Ok(SymbolType::Map(Box::new(key_type), Box::new(value_type)))
+    }
+
+    pub fn parse_map(&mut self) -> Result<AST, String> {
+        if !self.current_token_is(TokenType::LeftBrace) {
+            return Err(format!("Expected '{{' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '{'
+
+        let mut entries = Vec::new();
+        while !self.current_token_is(TokenType::RightBrace) {
+            let key = self.parse_expression()?;
+
+            if !self.current_token_is(TokenType::Colon) {
+                return Err(format!("Expected ':' after map key at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+
+            self.advance(); // Consume ':'
+
+            let value = self.parse_expression()?;
+            entries.push((key, value));
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightBrace) {
+                return Err(format!("Expected ',' or '}}' at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume '}'
+
+        Ok(AST::new(ASTNode::Map(entries)))
+    }
+
+    pub fn parse_index_assignment(&mut self, variable: String) -> Result<AST, String> {
+        self.advance(); // Consume '['
+
+        let index = self.parse_expression()?;
+
+        if !self.current_token_is(TokenType::RightBracket) {
+            return Err(format!("Expected ']' after index at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ']'
+
+        if !self.current_token_is(TokenType::Assign) {
+            return Err(format!("Expected ':=' at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ':='
+
+        let expression = self.parse_expression()?;
+
         if !self.current_token_is(TokenType::Termination) {
-            return Err(format!(
-                "Expected ';' at position {:?}. Found {:?}",
-                self.position, self.current_token
-            ));
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
         }
-    
-        self.advance();
-    
-        let expr_type = self.infer_type(&expression)?;
-        if expr_type != symbol_type {
+
+        self.advance(); // Consume ';'
+
+        let target = AST::new(ASTNode::Index {
+            collection: Box::new(AST::new(ASTNode::Identifier(variable.clone()))),
+            index: Box::new(index.clone()),
+        });
+        let target_type = self.infer_type(&target)?;
+        let expression_type = self.infer_type_as(&expression, &target_type)?;
+        if !target_type.accepts(&expression_type) {
             return Err(format!(
-                "Type mismatch: cannot assign expression of type {:?} to variable of type {:?} at position {:?}.",
-                expr_type, symbol_type, self.position
+                "Type mismatch: cannot assign expression of type {:?} to element of type {:?} at position {:?}.",
+                expression_type, target_type, self.position
             ));
         }
-    
-        let value = self.evaluate_expression(&expression)?;
-    
-        self.symbol_table.insert(variable.clone(), symbol_type, value)
-            .map_err(|e| format!("Error inserting symbol into symbol table: {}", e))?;
-    
-        Ok(AST::new(ASTNode::Assignment {
+
+        Ok(AST::new(ASTNode::IndexAssignment {
             variable,
+            index: Box::new(index),
             expression: Box::new(expression),
         }))
     }
+
+    /// Parses the `(...)` of `keys`, `values`, `contains_key` and `remove` once the method name is consumed.
+    fn parse_map_method(&mut self, map: AST, method_name: &str) -> Result<AST, String> {
+        if let (ASTNode::Identifier(name), "remove") = (&map.node, method_name) {
+            let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+            self.expect_mutable(name, "modify", position)?;
+        }
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after '{}' at position {:?}. Found {:?}", method_name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        let key = if method_name == "contains_key" || method_name == "remove" {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        if !self.current_token_is(TokenType::RightParen) {
+            return Err(format!("Expected ')' after '{}' at position {:?}. Found {:?}", method_name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ')'
+
+        let map = Box::new(map);
+        let node = match (method_name, key) {
+            ("keys", None) => ASTNode::Keys { map },
+            ("values", None) => ASTNode::Values { map },
+            ("contains_key", Some(key)) => ASTNode::ContainsKey { map, key: Box::new(key) },
+            ("remove", Some(key)) => ASTNode::Remove { map, key: Box::new(key) },
+            _ => return Err(format!("Unknown method '{}' at position {:?}.", method_name, self.position)),
+        };
+        let ast = AST::new(node);
+        self.infer_type(&ast)?;
+        Ok(ast)
+    }
+
+    /// Parses the arguments of `is_none()`, `unwrap_or(default)`, `try_pop()` and `get(index)`.
+    fn parse_option_method(&mut self, target: AST, method_name: &str) -> Result<AST, String> {
+        if let (ASTNode::Identifier(name), "try_pop") = (&target.node, method_name) {
+            let position = self.current_token.as_ref().map_or(self.position, |token| token.position);
+            self.expect_mutable(name, "modify", position)?;
+        }
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after '{}' at position {:?}. Found {:?}", method_name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        let argument = if method_name == "unwrap_or" || method_name == "get" {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        if !self.current_token_is(TokenType::RightParen) {
+            return Err(format!("Expected ')' after '{}' at position {:?}. Found {:?}", method_name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ')'
+
+        let target = Box::new(target);
+        let node = match (method_name, argument) {
+            ("is_none", None) => ASTNode::IsNone { value: target },
+            ("unwrap_or", Some(default)) => ASTNode::UnwrapOr { value: target, default: Box::new(default) },
+            ("try_pop", None) if matches!(target.node, ASTNode::Identifier(_)) => ASTNode::TryPop { list: target },
+            ("try_pop", None) => return Err(format!("try_pop requires a list variable at position {:?}.", self.position)),
+            ("get", Some(index)) => ASTNode::Get { collection: target, index: Box::new(index) },
+            _ => return Err(format!("Unknown method '{}' at position {:?}.", method_name, self.position)),
+        };
+        let ast = AST::new(node);
+        self.infer_type(&ast)?;
+        Ok(ast)
+    }
+
+    /// Optionals have to go through `is_none()` or `unwrap_or()` before they can be operands.
+    fn expect_handled(&self, operand_type: &SymbolType, operator: &str, position: Position) -> Result<(), String> {
+        match operand_type {
+            SymbolType::Option(_) => Err(format!(
+                "Type mismatch: optional value of type {:?} must be handled with is_none() or unwrap_or() before '{}' at position {:?}.",
+                operand_type, operator, position
+            )),
+            _ => Ok(()),
+        }
+    }
+
     pub fn parse_fetch(&mut self, list: AST) -> Result<AST, String> {
         if !self.current_token_is(TokenType::Dot) {
             return Err(format!("Expected '.' at position {:?}. Found {:?}", self.position, self.current_token));
@@ -431,8 +1919,8 @@ impl Parser {
     }
 
     pub fn parse_expression(&mut self) -> Result<AST, String> {
+        let marker = self.mark();
         let mut left = self.parse_term()?;
-        let mut left_type = self.infer_type(&left)?;
 
         while self.current_token_is(TokenType::Operator)
             || self.current_token_is(TokenType::LessThan)
@@ -443,10 +1931,14 @@ impl Parser {
             || self.current_token_is(TokenType::Or)
         {
             let operator = self.current_token.as_ref().unwrap().value.clone();
+            let position = self.current_token.as_ref().unwrap().position;
             self.advance();
 
             let right = self.parse_term()?;
+            let left_type = self.infer_type(&left)?;
             let right_type = self.infer_type(&right)?;
+            self.expect_handled(&left_type, &operator, position)?;
+            self.expect_handled(&right_type, &operator, position)?;
 
             if operator == "==" || operator == "!=" || operator == ">" || operator == "<" || operator == ">=" || operator == "<=" {
                 if left_type != right_type {
@@ -474,8 +1966,8 @@ impl Parser {
                 left: Box::new(left),
                 right: Box::new(right),
             });
+            self.finish_node(marker, NodeKind::Binary);
 
-            left_type = self.infer_type(&left)?;
         }
 
         Ok(left)
@@ -505,17 +1997,22 @@ impl Parser {
         Ok(AST::new(ASTNode::List(elements)))
     }
     pub fn parse_term(&mut self) -> Result<AST, String> {
+        let marker = self.mark();
         let mut left = self.parse_factor()?;
-        let mut left_type = self.infer_type(&left)?;
     
         while self.current_token_is(TokenType::Operator)
             && (self.current_token.as_ref().unwrap().value == "*"
-                || self.current_token.as_ref().unwrap().value == "/")
+                || self.current_token.as_ref().unwrap().value == "/"
+                || self.current_token.as_ref().unwrap().value == "%")
         {
             let operator = self.current_token.as_ref().unwrap().value.clone();
+            let position = self.current_token.as_ref().unwrap().position;
             self.advance();
             let right = self.parse_factor()?;
+            let left_type = self.infer_type(&left)?;
             let right_type = self.infer_type(&right)?;
+            self.expect_handled(&left_type, &operator, position)?;
+            self.expect_handled(&right_type, &operator, position)?;
     
             if left_type != right_type {
                 return Err(format!(
@@ -529,7 +2026,7 @@ impl Parser {
                 right: Box::new(right),
                 operator,
             });
-            left_type = self.infer_type(&left)?;
+            self.finish_node(marker, NodeKind::Binary);
         }
     
         Ok(left)
@@ -559,16 +2056,17 @@ impl Parser {
         Ok(expr)
     } */
     pub fn parse_factor(&mut self) -> Result<AST, String> {
+        let marker = self.mark();
         match self.current_token {
             Some(ref token) if token.token_type == TokenType::Number => {
-                let value = token.value.parse::<i64>()
-                    .map_err(|_| format!("Invalid integer format at position {:?}.", self.position))?;
+                let value = int_literal_value(&token.value)
+                    .map_err(|_| format!("Invalid integer format at position {:?}.", token.position))?;
                 self.advance();
                 Ok(AST::new(ASTNode::Int(value)))
             },
             Some(ref token) if token.token_type == TokenType::FloatNumber => {
-                let value = token.value.parse::<f64>()
-                    .map_err(|_| format!("Invalid float format at position {:?}.", self.position))?;
+                let value = float_literal_value(&token.value)
+                    .map_err(|_| format!("Invalid float format at position {:?}.", token.position))?;
                 self.advance();
                 Ok(AST::new(ASTNode::Float(value)))
             },
@@ -577,15 +2075,66 @@ impl Parser {
                 self.advance();
                 Ok(AST::new(ASTNode::Boolean(value)))
             },
+            Some(ref token) if token.token_type == TokenType::StringLiteral => self.parse_string_literal(),
+            Some(ref token) if token.token_type == TokenType::StdinLine => self.node(NodeKind::Call, |parser| parser.parse_stdin(InputKind::Line)),
+            Some(ref token) if token.token_type == TokenType::StdinInt => self.node(NodeKind::Call, |parser| parser.parse_stdin(InputKind::Int)),
+            Some(ref token) if token.token_type == TokenType::StdinFloat => self.node(NodeKind::Call, |parser| parser.parse_stdin(InputKind::Float)),
+            Some(ref token) if token.token_type == TokenType::ReadFile => {
+                let path = self.node(NodeKind::Call, |parser| parser.parse_path_argument("read_file"))?;
+                Ok(AST::new(ASTNode::ReadFile { path: Box::new(path) }))
+            },
+            Some(ref token) if token.token_type == TokenType::Match => self.node(NodeKind::Match, Self::parse_match),
+            Some(ref token) if token.token_type == TokenType::None => {
+                self.advance();
+                Ok(AST::new(ASTNode::None))
+            },
+            Some(ref token) if token.token_type == TokenType::ReadLines => {
+                let path = self.node(NodeKind::Call, |parser| parser.parse_path_argument("read_lines"))?;
+                Ok(AST::new(ASTNode::ReadLines { path: Box::new(path) }))
+            },
             Some(ref token) if token.token_type == TokenType::Identifier => {
                 let value = token.value.clone();
                 self.advance();
+                if self.current_token_is(TokenType::LeftBrace) && self.structs.contains_key(&value) {
+                    let literal = self.parse_struct_literal(value)?;
+                    self.finish_node(marker, NodeKind::StructLiteral);
+                    return Ok(literal);
+                }
+                if self.current_token_is(TokenType::Dot) && self.enums.contains_key(&value) {
+                    let variant = self.parse_enum_variant(value)?;
+                    self.finish_node(marker, NodeKind::EnumValue);
+                    return Ok(variant);
+                }
                 let mut expression = AST::new(ASTNode::Identifier(value));
 
-                // Check for method calls (fetch or len)
-                if self.current_token_is(TokenType::Dot) {
+                loop {
+                    // Each index, field or method call encloses the expression before it.
+                    if let Some(kind) = NodeKind::of(&expression.node) {
+                        self.finish_node(marker, kind);
+                    }
+                    if self.current_token_is(TokenType::LeftBracket) {
+                        let position = self.current_token.as_ref().unwrap().position;
+                        self.advance(); // Consume '['
+                        let index = self.parse_expression()?;
+                        self.expect_handled(&self.infer_type(&index)?, "[", position)?;
+                        if !self.current_token_is(TokenType::RightBracket) {
+                            return Err(format!("Expected ']' after index at position {:?}. Found {:?}", self.position, self.current_token));
+                        }
+                        self.advance(); // Consume ']'
+                        expression = AST::new(ASTNode::Index {
+                            collection: Box::new(expression),
+                            index: Box::new(index),
+                        });
+                        self.infer_type(&expression)?;
+                        continue;
+                    }
+                    if !self.current_token_is(TokenType::Dot) {
+                        break;
+                    }
+
+                    // Method calls (fetch, len, map methods), tuple indices and struct fields
                     self.advance(); // Consume the dot
-                
+
                     match self.current_token {
                         Some(ref token) if token.token_type == TokenType::Fetch => {
                             self.advance(); // Consume 'fetch'
@@ -595,6 +2144,32 @@ impl Parser {
                             self.advance(); // Consume 'len'
                             expression = self.parse_len(expression)?;
                         },
+                        Some(ref token) if token.token_type == TokenType::Number => {
+                            let index = token.value.parse::<usize>()
+                                .map_err(|_| format!("Invalid tuple index '{}' at position {:?}.", token.value, token.position))?;
+                            expression = AST::new(ASTNode::TupleIndex { tuple: Box::new(expression), index });
+                            self.infer_type(&expression)?;
+                            self.advance(); // Consume the index
+                        },
+                        Some(ref token) if token.token_type == TokenType::Identifier
+                            && matches!(self.infer_type(&expression), Ok(SymbolType::Struct { .. } | SymbolType::Error)) => {
+                            let field = token.value.clone();
+                            self.advance(); // Consume the field name
+                            expression = AST::new(ASTNode::FieldAccess { object: Box::new(expression), field });
+                            self.infer_type(&expression)?;
+                        },
+                        Some(ref token) if token.token_type == TokenType::Identifier
+                            && matches!(token.value.as_str(), "is_none" | "unwrap_or" | "try_pop" | "get") => {
+                            let method_name = token.value.clone();
+                            self.advance(); // Consume the method name
+                            expression = self.parse_option_method(expression, &method_name)?;
+                        },
+                        Some(ref token) if token.token_type == TokenType::Identifier
+                            && matches!(token.value.as_str(), "keys" | "values" | "contains_key" | "remove") => {
+                            let method_name = token.value.clone();
+                            self.advance(); // Consume the method name
+                            expression = self.parse_map_method(expression, &method_name)?;
+                        },
                         Some(ref token) => {
                             return Err(format!(
                                 "Unknown method '{:?}' at position {:?}.",
@@ -615,34 +2190,231 @@ impl Parser {
             Some(ref token) if token.token_type == TokenType::LeftParen => {
                 self.advance(); 
                 let expr = self.parse_expression()?;
+                if self.current_token_is(TokenType::Comma) {
+                    let mut elements = vec![expr];
+                    while self.current_token_is(TokenType::Comma) {
+                        self.advance(); // Consume ','
+                        elements.push(self.parse_expression()?);
+                    }
+                    if !self.current_token_is(TokenType::RightParen) {
+                        return Err(format!("Expected ',' or ')' in tuple at position {:?}. Found {:?}", self.position, self.current_token));
+                    }
+                    self.advance(); // Consume ')'
+                    self.finish_node(marker, NodeKind::Tuple);
+                    return Ok(AST::new(ASTNode::Tuple(elements)));
+                }
                 if !self.current_token_is(TokenType::RightParen) {
                     return Err(format!("Expected ')' at position {:?}. Found {:?}", self.position, self.current_token));
                 }
                 self.advance(); 
+                self.finish_node(marker, NodeKind::Paren);
                 Ok(expr)
             },
             Some(ref token) if token.token_type == TokenType::LeftBracket => {
-                self.parse_list()
+                self.node(NodeKind::List, Self::parse_list)
             },
-            _ => Err(format!("Unexpected token {:?} at position {:?}. Expected a number, float, identifier, boolean, or list.", self.current_token, self.position)),
+            Some(ref token) if token.token_type == TokenType::LeftBrace => {
+                self.node(NodeKind::Map, Self::parse_map)
+            },
+            _ => Err(format!("Unexpected token {:?} at position {:?}. Expected a number, float, string, identifier, boolean, list, or map.", self.current_token, self.position)),
         }
     }
 
-    pub fn parse_print(&mut self) -> Result<AST, String> {
-        if !self.current_token_is(TokenType::Print) {
-            return Err(format!("Expected 'stdout' at position {:?}. Found {:?}", self.position, self.current_token));
+    pub fn parse_string_literal(&mut self) -> Result<AST, String> {
+        let token = self.current_token.clone().unwrap();
+        self.advance(); // Consume the string literal
+
+        let file_source = self.source;
+        let segments = format::split_placeholders(&token.value).map_err(|(offset, message)| {
+            format!("{} at position {:?}.", message, literal_position(&token, offset, file_source))
+        })?;
+
+        let mut parsed = Vec::new();
+        for segment in segments {
+            match segment {
+                RawSegment::Literal(text) => parsed.push(FormatSegment::Literal(text)),
+                RawSegment::Placeholder { source, spec, offset, length } => {
+                    let start = literal_position(&token, offset, file_source);
+                    let end = literal_position(&token, offset + length - 1, file_source);
+                    let in_placeholder = |e: String| {
+                        format!("{} in placeholder '{{{}}}' at position {:?} to {:?}.", e.trim_end_matches('.'), source, start, end)
+                    };
+
+                    let expression = self.parse_embedded_expression(&source).map_err(in_placeholder)?;
+                    let expression_type = self.infer_type(&expression).map_err(in_placeholder)?;
+                    let is_float = match &expression_type {
+                        SymbolType::Float => true,
+                        SymbolType::List(element_type) => **element_type == SymbolType::Float,
+                        _ => false,
+                    };
+                    if spec.precision.is_some() && !is_float {
+                        return Err(in_placeholder(format!(
+                            "Precision can only be applied to float values or lists of floats, found {:?}",
+                            expression_type
+                        )));
+                    }
+
+                    parsed.push(FormatSegment::Placeholder { expression: Box::new(expression), spec });
+                }
+            }
+        }
+
+        match parsed.as_slice() {
+            [FormatSegment::Literal(text)] => Ok(AST::new(ASTNode::String(text.clone()))),
+            _ => Ok(AST::new(ASTNode::Format(parsed))),
+        }
+    }
+
+    /// Parses the source of a string-literal placeholder with the current symbol table.
+    fn parse_embedded_expression(&mut self, source: &str) -> Result<AST, String> {
+        let tokens = lexer(source)?;
+        let saved_tokens = std::mem::replace(&mut self.tokens, TokenStream::new(tokens.into_iter().map(Ok)));
+        let saved_token = self.current_token.take();
+        // The placeholder's tokens have spans into `source`, not into the file.
+        let saved_source = self.source.take();
+        self.advance();
+
+        let result = self.parse_expression().and_then(|expression| {
+            if self.current_token_is(TokenType::EOF) {
+                Ok(expression)
+            } else {
+                Err(format!("Unexpected token {:?} after expression", self.current_token))
+            }
+        });
+
+        self.tokens = saved_tokens;
+        self.current_token = saved_token;
+        self.source = saved_source;
+        result
+    }
+
+    pub fn parse_stdin(&mut self, kind: InputKind) -> Result<AST, String> {
+        self.advance(); // Consume 'stdin_line', 'stdin_int' or 'stdin_float'
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after stdin builtin at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        if !self.current_token_is(TokenType::RightParen) {
+            return Err(format!("Expected ')' after stdin builtin at position {:?}. Found {:?}", self.position, self.current_token));
+        }
+
+        self.advance(); // Consume ')'
+
+        Ok(AST::new(ASTNode::Stdin(kind)))
+    }
+
+    /// Parses `'(' (Expression (',' Expression)*)? ')'` after a builtin's name.
+    fn parse_call_arguments(&mut self, name: &str) -> Result<Vec<AST>, String> {
+        self.advance(); // Consume the builtin's name
+
+        if !self.current_token_is(TokenType::LeftParen) {
+            return Err(format!("Expected '(' after '{}' at position {:?}. Found {:?}", name, self.position, self.current_token));
+        }
+
+        self.advance(); // Consume '('
+
+        let mut arguments = Vec::new();
+        while !self.current_token_is(TokenType::RightParen) {
+            arguments.push(self.parse_expression()?);
+
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightParen) {
+                return Err(format!("Expected ',' or ')' after argument at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+        }
+
+        self.advance(); // Consume ')'
+
+        Ok(arguments)
+    }
+
+    fn expect_argument_types(&self, name: &str, arguments: &[AST], expected: &[SymbolType]) -> Result<(), String> {
+        if arguments.len() != expected.len() {
+            return Err(format!(
+                "'{}' expects {} argument(s), found {} at position {:?}.",
+                name, expected.len(), arguments.len(), self.position
+            ));
+        }
+        for (argument, expected_type) in arguments.iter().zip(expected) {
+            let argument_type = self.infer_type(argument)?;
+            if !expected_type.accepts(&argument_type) {
+                return Err(format!(
+                    "Type mismatch: '{}' expects an argument of type {:?}, found {:?} at position {:?}.",
+                    name, expected_type, argument_type, self.position
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_path_argument(&mut self, name: &str) -> Result<AST, String> {
+        let mut arguments = self.parse_call_arguments(name)?;
+        self.expect_argument_types(name, &arguments, &[SymbolType::String])?;
+        Ok(arguments.remove(0))
+    }
+
+    pub fn parse_write_file(&mut self) -> Result<AST, String> {
+        let mut arguments = self.parse_call_arguments("write_file")?;
+        self.expect_argument_types("write_file", &arguments, &[SymbolType::String, SymbolType::String])?;
+
+        if !self.current_token_is(TokenType::Termination) {
+            return Err(format!("Expected ';' at position {:?}. Found {:?}", self.position, self.current_token));
         }
 
+        self.advance(); // Consume ';'
+
+        let contents = arguments.remove(1);
+        let path = arguments.remove(0);
+        Ok(AST::new(ASTNode::WriteFile { path: Box::new(path), contents: Box::new(contents) }))
+    }
+
+    pub fn parse_print(&mut self) -> Result<AST, String> {
+        let (name, stream, newline) = match self.current_token {
+            Some(ref token) if token.token_type == TokenType::Print => ("stdout", OutputStream::Stdout, true),
+            Some(ref token) if token.token_type == TokenType::PrintInline => ("print", OutputStream::Stdout, false),
+            Some(ref token) if token.token_type == TokenType::PrintError => ("stderr", OutputStream::Stderr, true),
+            _ => return Err(format!("Expected 'stdout', 'print' or 'stderr' at position {:?}. Found {:?}", self.position, self.current_token)),
+        };
+
         self.advance(); 
         if !self.current_token_is(TokenType::LeftParen) || self.current_token.as_ref().unwrap().value != "(" {
-            return Err(format!("Expected '(' after 'stdout' at position {:?}. Found {:?}", self.position, self.current_token));
+            return Err(format!("Expected '(' after '{}' at position {:?}. Found {:?}", name, self.position, self.current_token));
         }
 
         self.advance(); 
-        let expression = self.parse_expression()?;
+        let mut arguments = Vec::new();
+        let mut separator = None;
+        while !self.current_token_is(TokenType::RightParen) {
+            if separator.is_some() {
+                return Err(format!("Expected ')' after the 'sep:' argument at position {:?}. Found {:?}", self.position, self.current_token));
+            }
+            if self.current_token.as_ref().is_some_and(|token| token.token_type == TokenType::Identifier && token.value == "sep")
+                && self.tokens.peek(0).is_some_and(|token| token.token_type == TokenType::Colon)
+            {
+                self.advance(); // Consume 'sep'
+                self.advance(); // Consume ':'
+                let expression = self.parse_expression()?;
+                let separator_type = self.infer_type(&expression)?;
+                if separator_type != SymbolType::String {
+                    return Err(format!(
+                        "Type mismatch: '{}' expects a 'sep:' of type String, found {:?} at position {:?}.",
+                        name, separator_type, self.position
+                    ));
+                }
+                separator = Some(Box::new(expression));
+            } else {
+                arguments.push(self.parse_expression()?);
+            }
 
-        if !self.current_token_is(TokenType::RightParen) || self.current_token.as_ref().unwrap().value != ")" {
-            return Err(format!("Expected ')' after expression at position {:?}. Found {:?}", self.position, self.current_token));
+            if self.current_token_is(TokenType::Comma) {
+                self.advance(); // Consume ','
+            } else if !self.current_token_is(TokenType::RightParen) {
+                return Err(format!("Expected ',' or ')' after expression at position {:?}. Found {:?}", self.position, self.current_token));
+            }
         }
 
         self.advance(); 
@@ -652,7 +2424,7 @@ impl Parser {
         }
 
         self.advance(); 
-Ok(AST::new(ASTNode::Print(Box::new(expression))))
+        Ok(AST::new(ASTNode::Print { arguments, separator, stream, newline }))
     }
 
     pub fn infer_type(&self, ast: &AST) -> Result<SymbolType, String> {
@@ -660,6 +2432,11 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
             ASTNode::Int(_) => Ok(SymbolType::Int),
             ASTNode::Float(_) => Ok(SymbolType::Float),
             ASTNode::Boolean(_) => Ok(SymbolType::Boolean),
+            ASTNode::String(_) => Ok(SymbolType::String),
+            ASTNode::Stdin(kind) => Ok(kind.symbol_type()),
+            ASTNode::Format(_) => Ok(SymbolType::String),
+            ASTNode::ReadFile { .. } => Ok(SymbolType::String),
+            ASTNode::ReadLines { .. } => Ok(SymbolType::List(Box::new(SymbolType::String))),
             ASTNode::Identifier(name) => self.symbol_table.lookup(name)
                 .map(|symbol| symbol.symbol_type.clone())
                 .ok_or_else(|| format!("Undefined variable: {}", name)),
@@ -681,9 +2458,11 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                 let right_type = self.infer_type(right)?;
     
                 match operator.as_str() {
-                    "+" | "-" | "*" | "/" => {
+                    "+" | "-" | "*" | "/" | "%" => {
+                        let concatenation = operator == "+" && matches!(left_type, SymbolType::List(_));
                         if (left_type == SymbolType::Int && right_type == SymbolType::Int) ||
-                           (left_type == SymbolType::Float && right_type == SymbolType::Float) {
+                           (left_type == SymbolType::Float && right_type == SymbolType::Float) ||
+                           (concatenation && left_type == right_type) {
                             Ok(left_type)
                         } else {
                             Err(format!("Type mismatch: cannot perform '{}' operation between {:?} and {:?}.", operator, left_type, right_type))
@@ -705,7 +2484,7 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                 let value_type = self.infer_type(value)?;
     
                 if let SymbolType::List(element_type) = list_type {
-                    if *element_type == value_type {
+                    if element_type.accepts(&value_type) {
                         Ok(SymbolType::List(element_type))
                     } else {
                         Err(format!("Type mismatch: cannot push value of type {:?} to list of type {:?}.", value_type, element_type))
@@ -737,6 +2516,126 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                     Err(format!("Type mismatch: len operation can only be performed on lists, found {:?}.", list_type))
                 }
             }
+            ASTNode::Map(entries) => {
+                if entries.is_empty() {
+                    return Err("Cannot infer type of empty map.".to_string());
+                }
+                let key_type = self.infer_type(&entries[0].0)?;
+                let value_type = self.infer_type(&entries[0].1)?;
+                if !key_type.is_map_key() {
+                    return Err(format!("Map keys must be of type Int, Boolean, or String, found {:?}.", key_type));
+                }
+                for (key, value) in entries.iter().skip(1) {
+                    let (k, v) = (self.infer_type(key)?, self.infer_type(value)?);
+                    if k != key_type || v != value_type {
+                        return Err(format!(
+                            "Type mismatch in map entries: {{{:?}: {:?}}} and {{{:?}: {:?}}}.",
+                            key_type, value_type, k, v
+                        ));
+                    }
+                }
+                Ok(SymbolType::Map(Box::new(key_type), Box::new(value_type)))
+            }
+            ASTNode::Tuple(elements) => {
+                let mut element_types = Vec::new();
+                for element in elements {
+                    element_types.push(self.infer_type(element)?);
+                }
+                Ok(SymbolType::Tuple(element_types))
+            }
+            ASTNode::EnumVariant { enum_type, .. } => Ok(enum_type.clone()),
+            ASTNode::None => Ok(SymbolType::none_type()),
+            ASTNode::IsNone { value } => match self.infer_type(value)? {
+                SymbolType::Option(_) => Ok(SymbolType::Boolean),
+                other => Err(format!("Type mismatch: is_none can only be performed on optionals, found {:?}.", other)),
+            },
+            ASTNode::UnwrapOr { value, default } => {
+                let default_type = self.infer_type(default)?;
+                match self.infer_type(value)? {
+                    SymbolType::Option(inner) if *inner == SymbolType::Void => Ok(default_type),
+                    SymbolType::Option(inner) if *inner == default_type => Ok(*inner),
+                    SymbolType::Option(inner) => Err(format!(
+                        "Type mismatch: unwrap_or default of type {:?} does not match {:?}.", default_type, inner
+                    )),
+                    other => Err(format!("Type mismatch: unwrap_or can only be performed on optionals, found {:?}.", other)),
+                }
+            }
+            ASTNode::TryPop { list } => match self.infer_type(list)? {
+                SymbolType::List(element_type) => Ok(SymbolType::Option(element_type)),
+                other => Err(format!("Type mismatch: try_pop can only be performed on lists, found {:?}.", other)),
+            },
+            ASTNode::Get { collection, index } => {
+                let index_type = self.infer_type(index)?;
+                match self.infer_type(collection)? {
+                    SymbolType::List(element_type) if index_type == SymbolType::Int => Ok(SymbolType::Option(element_type)),
+                    SymbolType::List(_) => Err(format!("Type mismatch: index must be of type Int, found {:?}.", index_type)),
+                    SymbolType::Map(key_type, value_type) if *key_type == index_type => Ok(SymbolType::Option(value_type)),
+                    SymbolType::Map(key_type, _) => Err(format!(
+                        "Type mismatch: map key must be of type {:?}, found {:?}.", key_type, index_type
+                    )),
+                    other => Err(format!("Type mismatch: get can only be performed on lists and maps, found {:?}.", other)),
+                }
+            }
+            ASTNode::Match { result_type, .. } => Ok(result_type.clone()),
+            ASTNode::StructLiteral { name, .. } => self.structs.get(name).cloned()
+                .ok_or_else(|| format!("Unknown struct '{}'.", name)),
+            ASTNode::FieldAccess { object, field } => {
+                match self.infer_type(object)? {
+                    SymbolType::Struct { name, fields } => fields.iter()
+                        .find(|(declared, _)| declared == field)
+                        .map(|(_, field_type)| field_type.clone())
+                        .ok_or_else(|| format!("Struct '{}' has no field '{}'.", name, field)),
+                    SymbolType::Error => SymbolType::error_field_type(field)
+                        .ok_or_else(|| format!("Error values have no field '{}'.", field)),
+                    other => Err(format!("Type mismatch: field access '.{}' can only be performed on structs, found {:?}.", field, other)),
+                }
+            }
+            ASTNode::TupleIndex { tuple, index } => {
+                match self.infer_type(tuple)? {
+                    SymbolType::Tuple(element_types) => element_types.get(*index).cloned().ok_or_else(|| format!(
+                        "Tuple index {} out of bounds for a tuple of {} elements.", index, element_types.len()
+                    )),
+                    other => Err(format!("Type mismatch: '.{}' can only be performed on tuples, found {:?}.", index, other)),
+                }
+            }
+            ASTNode::Index { collection, index } => {
+                let collection_type = self.infer_type(collection)?;
+                let index_type = self.infer_type(index)?;
+
+                match collection_type {
+                    SymbolType::List(element_type) if index_type == SymbolType::Int => Ok(*element_type),
+                    SymbolType::List(_) => Err(format!("Type mismatch: index must be of type Int, found {:?}.", index_type)),
+                    SymbolType::Map(key_type, value_type) if *key_type == index_type => Ok(*value_type),
+                    SymbolType::Map(key_type, _) => Err(format!(
+                        "Type mismatch: map key must be of type {:?}, found {:?}.", key_type, index_type
+                    )),
+                    other => Err(format!("Type mismatch: indexing can only be performed on lists and maps, found {:?}.", other)),
+                }
+            }
+            ASTNode::Keys { map } | ASTNode::Values { map } => {
+                match self.infer_type(map)? {
+                    SymbolType::Map(key_type, value_type) => Ok(SymbolType::List(
+                        if matches!(ast.node, ASTNode::Keys { .. }) { key_type } else { value_type }
+                    )),
+                    other => Err(format!("Type mismatch: keys and values can only be performed on maps, found {:?}.", other)),
+                }
+            }
+            ASTNode::ContainsKey { map, key } | ASTNode::Remove { map, key } => {
+                let key_type = self.infer_type(key)?;
+                match self.infer_type(map)? {
+                    SymbolType::Map(expected_key, value_type) if *expected_key == key_type => {
+                        if matches!(ast.node, ASTNode::ContainsKey { .. }) {
+                            Ok(SymbolType::Boolean)
+                        } else {
+                            Ok(*value_type)
+                        }
+                    }
+                    SymbolType::Map(expected_key, _) => Err(format!(
+                        "Type mismatch: map key must be of type {:?}, found {:?}.", expected_key, key_type
+                    )),
+                    other => Err(format!("Type mismatch: contains_key and remove can only be performed on maps, found {:?}.", other)),
+                }
+            }
             _ => Err(format!("Unknown AST node: {:?}", ast.node)),
         }
     }
@@ -745,6 +2644,22 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
             ASTNode::Int(value) => Ok(SymbolValue::Int(*value)),
             ASTNode::Float(value) => Ok(SymbolValue::Float(*value)),
             ASTNode::Boolean(value) => Ok(SymbolValue::Boolean(*value)),
+            ASTNode::String(value) => Ok(SymbolValue::String(value.clone())),
+            // Input only exists at run time; the interpreter replaces this placeholder.
+            ASTNode::Stdin(kind) => Ok(kind.symbol_type().default_value()),
+            ASTNode::ReadFile { .. } | ASTNode::ReadLines { .. } => self.infer_type(ast).map(|t| t.default_value()),
+            ASTNode::Format(segments) => {
+                let mut text = String::new();
+                for segment in segments {
+                    match segment {
+                        FormatSegment::Literal(literal) => text.push_str(literal),
+                        FormatSegment::Placeholder { expression, spec } => {
+                            text.push_str(&format::render(&self.evaluate_expression(expression)?, spec));
+                        }
+                    }
+                }
+                Ok(SymbolValue::String(text))
+            }
             ASTNode::Identifier(ref name) => {
                 self.symbol_table.lookup(name)
                     .map(|symbol| symbol.value.clone())
@@ -765,6 +2680,10 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                     "+" => match (left_value.clone(), right_value.clone()) {
                         (SymbolValue::Int(l), SymbolValue::Int(r)) => Ok(SymbolValue::Int(l + r)),
                         (SymbolValue::Float(l), SymbolValue::Float(r)) => Ok(SymbolValue::Float(l + r)),
+                        (SymbolValue::List(mut l), SymbolValue::List(r)) => {
+                            l.extend(r);
+                            Ok(SymbolValue::List(l))
+                        },
                         _ => Err(format!("Type mismatch for '+' operation. Left: {:?}, Right: {:?}", left_value, right_value)),
                     },
                     "-" => match (left_value.clone(), right_value.clone()) {
@@ -778,10 +2697,15 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                         _ => Err(format!("Type mismatch for '*' operation. Left: {:?}, Right: {:?}", left_value, right_value)),
                     },
                     "/" => match (left_value.clone(), right_value.clone()) {
-                        (SymbolValue::Int(l), SymbolValue::Int(r)) => Ok(SymbolValue::Int(l / r)),
+                        (SymbolValue::Int(l), SymbolValue::Int(r)) => l.checked_div(r).map(SymbolValue::Int).ok_or_else(|| "Division by zero.".to_string()),
                         (SymbolValue::Float(l), SymbolValue::Float(r)) => Ok(SymbolValue::Float(l / r)),
                         _ => Err(format!("Type mismatch for '/' operation. Left: {:?}, Right: {:?}", left_value, right_value)),
                     },
+                    "%" => match (left_value.clone(), right_value.clone()) {
+                        (SymbolValue::Int(l), SymbolValue::Int(r)) => l.checked_rem(r).map(SymbolValue::Int).ok_or_else(|| "Division by zero.".to_string()),
+                        (SymbolValue::Float(l), SymbolValue::Float(r)) => Ok(SymbolValue::Float(l % r)),
+                        _ => Err(format!("Type mismatch for '%' operation. Left: {:?}, Right: {:?}", left_value, right_value)),
+                    },
                     "==" => Ok(SymbolValue::Boolean(left_value == right_value)),
                     "!=" => Ok(SymbolValue::Boolean(left_value != right_value)),
                     ">" => match (left_value.clone(), right_value.clone()) {
@@ -853,18 +2777,100 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
                     Err("Len operation can only be performed on lists.".to_string())
                 }
             }
+            ASTNode::Map(entries) => {
+                let mut map = SymbolValue::Map(Vec::new());
+                for (key, value) in entries {
+                    map.set_index(self.evaluate_expression(key)?, self.evaluate_expression(value)?)?;
+                }
+                Ok(map)
+            }
+            ASTNode::Index { collection, index } => {
+                self.evaluate_expression(collection)?.index(&self.evaluate_expression(index)?)
+            }
+            ASTNode::Tuple(elements) => {
+                let mut values = Vec::new();
+                for element in elements {
+                    values.push(self.evaluate_expression(element)?);
+                }
+                Ok(SymbolValue::Tuple(values))
+            }
+            ASTNode::StructLiteral { name, fields } => {
+                let mut values = Vec::new();
+                for (field, value) in fields {
+                    values.push((field.clone(), self.evaluate_expression(value)?));
+                }
+                Ok(SymbolValue::Struct { name: name.clone(), fields: values })
+            }
+            ASTNode::FieldAccess { object, field } => self.evaluate_expression(object)?.field(field),
+            ASTNode::EnumVariant { enum_type, variant, values } => {
+                let name = match enum_type {
+                    SymbolType::Enum { name, .. } => name.clone(),
+                    other => return Err(format!("Expected an enum type, found {:?}.", other)),
+                };
+                let mut payload = Vec::new();
+                for value in values {
+                    payload.push(self.evaluate_expression(value)?);
+                }
+                Ok(SymbolValue::Enum { name, variant: variant.clone(), values: payload })
+            }
+            ASTNode::None => Ok(SymbolValue::None),
+            ASTNode::IsNone { value } => Ok(SymbolValue::Boolean(self.evaluate_expression(value)? == SymbolValue::None)),
+            ASTNode::UnwrapOr { value, default } => match self.evaluate_expression(value)? {
+                SymbolValue::None => self.evaluate_expression(default),
+                value => Ok(value),
+            },
+            // The list is only popped when the interpreter runs the program.
+            ASTNode::TryPop { .. } => Ok(SymbolValue::None),
+            ASTNode::Get { collection, index } => {
+                Ok(self.evaluate_expression(collection)?.get(&self.evaluate_expression(index)?))
+            }
+            // Arm bindings are only in scope while the match is parsed; the interpreter evaluates it.
+            ASTNode::Match { result_type, .. } => Ok(result_type.default_value()),
+            ASTNode::TupleIndex { tuple, index } => {
+                match self.evaluate_expression(tuple)? {
+                    SymbolValue::Tuple(values) => values.get(*index).cloned()
+                        .ok_or_else(|| format!("Tuple index {} out of bounds.", index)),
+                    _ => Err("Tuple indexing can only be performed on tuples.".to_string()),
+                }
+            }
+            ASTNode::Keys { map } | ASTNode::Values { map } => {
+                let keys = matches!(ast.node, ASTNode::Keys { .. });
+                match self.evaluate_expression(map)? {
+                    SymbolValue::Map(entries) => Ok(SymbolValue::List(
+                        entries.into_iter().map(|(k, v)| if keys { k } else { v }).collect()
+                    )),
+                    _ => Err("keys and values can only be performed on maps.".to_string()),
+                }
+            }
+            ASTNode::ContainsKey { map, key } => {
+                let key = self.evaluate_expression(key)?;
+                self.evaluate_expression(map)?.contains_key(&key).map(SymbolValue::Boolean)
+            }
             _ => Err(format!("Cannot evaluate AST node {:?}", ast.node)),
         }
     }
     pub fn advance(&mut self) {
-        if self.current_index < self.tokens.len() {
-            self.current_token = Some(self.tokens[self.current_index].clone());
-            self.current_index += 1;
-        } else {
-            self.current_token = None;
+        self.consumed_end = self.tokens.consumed;
+        self.current_token = self.tokens.next();
+        if self.current_token_is(TokenType::DocComment) && !self.documents_declaration() {
+            // Doc comments that document nothing are plain comments.
+            while self.current_token_is(TokenType::DocComment) {
+                self.current_token = self.tokens.next();
+            }
         }
     }
 
+    /// Whether the doc comments starting at the current token are followed by a declaration.
+    fn documents_declaration(&mut self) -> bool {
+        let mut n = 0;
+        while self.tokens.peek(n).is_some_and(|token| token.token_type == TokenType::DocComment) {
+            n += 1;
+        }
+        self.tokens.peek(n).is_some_and(|token| {
+            matches!(token.token_type, TokenType::Let | TokenType::Const | TokenType::Struct | TokenType::Enum)
+        })
+    }
+
     pub fn current_token_is(&self, token_type: TokenType) -> bool {
         self.current_token
             .as_ref()
@@ -872,3 +2878,35 @@ Ok(AST::new(ASTNode::Print(Box::new(expression))))
             .unwrap_or(false)
     }
 }
+
+/// The source position of the character at `offset` in a string literal's value. Given the
+/// source, an escape counts as the two characters written; otherwise the value is all there is.
+fn literal_position(token: &Token, offset: usize, source: Option<&str>) -> Position {
+    // The literal's text starts right after its opening quote.
+    let mut position = Position { line: token.position.line, column: token.position.column + 1 };
+    let mut advance = |ch: char, width: usize| {
+        if ch == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += width;
+        }
+    };
+    match source.and_then(|source| source.get(token.span.clone())).and_then(|text| text.strip_prefix('"')) {
+        Some(text) => {
+            let mut chars = text.chars();
+            for _ in 0..offset {
+                match chars.next() {
+                    Some('\\') => {
+                        chars.next();
+                        advance('\\', 2);
+                    }
+                    Some(ch) => advance(ch, 1),
+                    None => break,
+                }
+            }
+        }
+        None => token.value.chars().take(offset).for_each(|ch| advance(ch, 1)),
+    }
+    position
+}
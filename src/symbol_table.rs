@@ -1,11 +1,27 @@
+use crate::models::Position;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum SymbolType {
     Int,
     Float,
     Boolean,
+    String,
     List(Box<SymbolType>), 
+    Map(Box<SymbolType>, Box<SymbolType>),
+    Tuple(Vec<SymbolType>),
+    Struct {
+        name: String,
+        fields: Vec<(String, SymbolType)>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, Vec<SymbolType>)>,
+    },
+    /// `T?`; `Option(Void)` is the type of the `none` literal.
+    Option(Box<SymbolType>),
+    Error,
     Void, 
 }
 
@@ -14,13 +30,197 @@ pub enum SymbolValue {
     Int(i64),
     Float(f64),
     Boolean(bool),
+    String(String),
     List(Vec<SymbolValue>),
+    /// Entries in insertion order, which is also the iteration order.
+    Map(Vec<(SymbolValue, SymbolValue)>),
+    Tuple(Vec<SymbolValue>),
+    /// Fields in declaration order.
+    Struct {
+        name: String,
+        fields: Vec<(String, SymbolValue)>,
+    },
+    Enum {
+        name: String,
+        variant: String,
+        values: Vec<SymbolValue>,
+    },
+    /// The absent value of an optional. A present value is stored as the plain value.
+    None,
+    /// A thrown or caught runtime error and the position of the statement that raised it.
+    Error {
+        message: String,
+        position: Position,
+    },
+}
+
+impl SymbolType {
+    /// The value a variable of this type holds before anything is assigned to it.
+    pub fn default_value(&self) -> SymbolValue {
+        match self {
+            SymbolType::Int => SymbolValue::Int(0),
+            SymbolType::Float => SymbolValue::Float(0.0),
+            SymbolType::Boolean => SymbolValue::Boolean(false),
+            SymbolType::String => SymbolValue::String(String::new()),
+            SymbolType::List(_) | SymbolType::Void => SymbolValue::List(Vec::new()),
+            SymbolType::Map(_, _) => SymbolValue::Map(Vec::new()),
+            SymbolType::Tuple(types) => SymbolValue::Tuple(types.iter().map(|t| t.default_value()).collect()),
+            SymbolType::Struct { name, fields } => SymbolValue::Struct {
+                name: name.clone(),
+                fields: fields.iter().map(|(field, t)| (field.clone(), t.default_value())).collect(),
+            },
+            SymbolType::Option(_) => SymbolValue::None,
+            SymbolType::Error => SymbolValue::Error {
+                message: String::new(),
+                position: Position { line: 0, column: 0 },
+            },
+            // The first variant, like the first field of a struct, stands in until the real value is known.
+            SymbolType::Enum { name, variants } => {
+                let (variant, payload) = &variants[0];
+                SymbolValue::Enum {
+                    name: name.clone(),
+                    variant: variant.clone(),
+                    values: payload.iter().map(|t| t.default_value()).collect(),
+                }
+            }
+        }
+    }
+
+    /// The type of the `none` literal, which every optional type accepts.
+    pub fn none_type() -> SymbolType {
+        SymbolType::Option(Box::new(SymbolType::Void))
+    }
+
+    /// Whether a value of `value_type` can be stored where this type is expected. An optional
+    /// accepts `none` and values of its inner type as well as optionals of the same type.
+    pub fn accepts(&self, value_type: &SymbolType) -> bool {
+        match self {
+            SymbolType::Option(inner) => {
+                self == value_type || **inner == *value_type || *value_type == SymbolType::none_type()
+            }
+            _ => self == value_type,
+        }
+    }
+
+    /// The type of `.message`, `.line` or `.column` on an error value.
+    pub fn error_field_type(field: &str) -> Option<SymbolType> {
+        match field {
+            "message" => Some(SymbolType::String),
+            "line" | "column" => Some(SymbolType::Int),
+            _ => None,
+        }
+    }
+
+    /// Whether values of this type can be used as map keys.
+    pub fn is_map_key(&self) -> bool {
+        matches!(self, SymbolType::Int | SymbolType::Boolean | SymbolType::String)
+    }
+}
+
+impl SymbolValue {
+    /// `value[index]` for lists (by position) and maps (by key).
+    pub fn index(&self, index: &SymbolValue) -> Result<SymbolValue, String> {
+        match (self, index) {
+            (SymbolValue::List(elements), SymbolValue::Int(i)) => {
+                if *i >= 0 && (*i as usize) < elements.len() {
+                    Ok(elements[*i as usize].clone())
+                } else {
+                    Err(format!("Index {} out of bounds.", i))
+                }
+            }
+            (SymbolValue::List(_), _) => Err("Index must be an integer.".to_string()),
+            (SymbolValue::Map(entries), key) => entries.iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .ok_or_else(|| format!("Key {} not found in map.", key)),
+            _ => Err("Indexing can only be performed on lists and maps.".to_string()),
+        }
+    }
+
+    /// `value[index] := new_value`. Lists must already have the index; maps insert missing keys.
+    pub fn set_index(&mut self, index: SymbolValue, new_value: SymbolValue) -> Result<(), String> {
+        match (self, index) {
+            (SymbolValue::List(elements), SymbolValue::Int(i)) => {
+                if i >= 0 && (i as usize) < elements.len() {
+                    elements[i as usize] = new_value;
+                    Ok(())
+                } else {
+                    Err(format!("Index {} out of bounds.", i))
+                }
+            }
+            (SymbolValue::List(_), _) => Err("Index must be an integer.".to_string()),
+            (SymbolValue::Map(entries), key) => {
+                match entries.iter_mut().find(|(k, _)| *k == key) {
+                    Some(entry) => entry.1 = new_value,
+                    None => entries.push((key, new_value)),
+                }
+                Ok(())
+            }
+            _ => Err("Indexing can only be performed on lists and maps.".to_string()),
+        }
+    }
+
+    pub fn field(&self, field: &str) -> Result<SymbolValue, String> {
+        match self {
+            SymbolValue::Struct { name, fields } => fields.iter()
+                .find(|(f, _)| f == field)
+                .map(|(_, v)| v.clone())
+                .ok_or_else(|| format!("Struct '{}' has no field '{}'.", name, field)),
+            SymbolValue::Error { message, position } => match field {
+                "message" => Ok(SymbolValue::String(message.clone())),
+                "line" => Ok(SymbolValue::Int(position.line as i64)),
+                "column" => Ok(SymbolValue::Int(position.column as i64)),
+                _ => Err(format!("Error values have no field '{}'.", field)),
+            },
+            _ => Err(format!("Field access '.{}' can only be performed on structs.", field)),
+        }
+    }
+
+    /// Assigns `new_value` to the field reached by following `path`, e.g. `["a", "b"]` for `.a.b`.
+    pub fn set_field(&mut self, path: &[String], new_value: SymbolValue) -> Result<(), String> {
+        let Some((first, rest)) = path.split_first() else {
+            *self = new_value;
+            return Ok(());
+        };
+        match self {
+            SymbolValue::Struct { name, fields } => match fields.iter_mut().find(|(f, _)| f == first) {
+                Some((_, value)) => value.set_field(rest, new_value),
+                None => Err(format!("Struct '{}' has no field '{}'.", name, first)),
+            },
+            _ => Err(format!("Field access '.{}' can only be performed on structs.", first)),
+        }
+    }
+
+    /// Like `index`, but a missing element or key is `None` instead of an error.
+    pub fn get(&self, index: &SymbolValue) -> SymbolValue {
+        self.index(index).unwrap_or(SymbolValue::None)
+    }
+
+    pub fn contains_key(&self, key: &SymbolValue) -> Result<bool, String> {
+        match self {
+            SymbolValue::Map(entries) => Ok(entries.iter().any(|(k, _)| k == key)),
+            _ => Err("contains_key can only be performed on maps.".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mutability {
+    /// `const`, evaluated while parsing.
+    Constant,
+    /// `let`, and bindings introduced by patterns and `catch`.
+    Immutable,
+    /// `let mut`.
+    Mutable,
 }
 
 #[derive(Debug, Clone)]
 pub struct Symbol {
     pub symbol_type: SymbolType,
     pub value: SymbolValue,
+    pub mutability: Mutability,
+    /// Where the declaration appears in the source, if it was written by the user.
+    pub declared_at: Option<Position>,
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +228,12 @@ pub struct SymbolTable {
     scopes: Vec<HashMap<String, Symbol>>,
 }
 
+impl Default for SymbolTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SymbolTable {
     pub fn new() -> Self {
         SymbolTable {
@@ -35,18 +241,35 @@ impl SymbolTable {
         }
     }
 
+    /// Declares an immutable symbol with no source position in the current scope.
     pub fn insert(&mut self, name: String, symbol_type: SymbolType, value: SymbolValue) -> Result<(), String> {
+        self.declare(name, Symbol { symbol_type, value, mutability: Mutability::Immutable, declared_at: None })
+    }
+
+    pub fn declare(&mut self, name: String, symbol: Symbol) -> Result<(), String> {
         let current_scope = self.scopes.last_mut().unwrap();
-        if current_scope.contains_key(&name) {
-            Err(format!("Symbol '{}' already declared", name))
-        } else {
-            current_scope.insert(name, Symbol { symbol_type, value });
-            Ok(())
+        match current_scope.entry(name) {
+            Entry::Occupied(entry) => Err(format!("Symbol '{}' already declared", entry.key())),
+            Entry::Vacant(entry) => {
+                entry.insert(symbol);
+                Ok(())
+            }
         }
     }
 
 
 
+    /// Binds `name` in the current scope, replacing an earlier binding in that same scope.
+    /// Used when a declaration runs again, e.g. on the next iteration of a loop.
+    pub fn define(&mut self, name: String, symbol: Symbol) {
+        self.scopes.last_mut().unwrap().insert(name, symbol);
+    }
+
+    /// Looks `name` up in the innermost scope only.
+    pub fn lookup_current(&self, name: &str) -> Option<&Symbol> {
+        self.scopes.last().unwrap().get(name)
+    }
+
     pub fn update(&mut self, name: String, value: SymbolValue) -> Result<(), String> {
         for scope in self.scopes.iter_mut().rev() {
             if let Some(symbol) = scope.get_mut(&name) {
@@ -97,6 +320,41 @@ impl SymbolTable {
     }
 
 
+    pub fn set_index(&mut self, name: &str, index: SymbolValue, value: SymbolValue) -> Result<(), String> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(symbol) = scope.get_mut(name) {
+                return symbol.value.set_index(index, value);
+            }
+        }
+        Err(format!("Symbol '{}' not found", name))
+    }
+
+    pub fn set_field(&mut self, name: &str, path: &[String], value: SymbolValue) -> Result<(), String> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(symbol) = scope.get_mut(name) {
+                return symbol.value.set_field(path, value);
+            }
+        }
+        Err(format!("Symbol '{}' not found", name))
+    }
+
+    pub fn remove(&mut self, map_name: &str, key: &SymbolValue) -> Result<SymbolValue, String> {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(symbol) = scope.get_mut(map_name) {
+                if let SymbolValue::Map(ref mut entries) = symbol.value {
+                    if let Some(position) = entries.iter().position(|(k, _)| k == key) {
+                        return Ok(entries.remove(position).1);
+                    } else {
+                        return Err(format!("Key {} not found in map '{}'", key, map_name));
+                    }
+                } else {
+                    return Err(format!("Symbol '{}' is not a map", map_name));
+                }
+            }
+        }
+        Err(format!("Symbol '{}' not found", map_name))
+    }
+
     pub fn enter_scope(&mut self) {
         self.scopes.push(HashMap::new());
     }
@@ -106,7 +364,7 @@ impl SymbolTable {
     }
 
     pub fn print(&self) {
-        for (_i, _scope) in self.scopes.iter().enumerate() {
+        for _scope in self.scopes.iter() {
             //println!("Scope {}: {:?}", i, scope);
         }
     }
@@ -0,0 +1,224 @@
+//! The declarative description of calru's tokens. `lexer::Lexer` matches source text
+//! against these tables, and `highlighting_grammar` exports them for editors.
+
+use crate::models::TokenType;
+
+/// A fixed word of the language: its text, the token it lexes as, and the TextMate scope
+/// editors highlight it with, if any. A new keyword or operator is one row of `KEYWORDS` or
+/// `SYMBOLS`; only one with syntax of its own also needs a `TokenType` for the parser.
+pub type Word = (&'static str, TokenType, Option<&'static str>);
+
+const STORAGE: Option<&str> = Some("storage.type.calru");
+const MODIFIER: Option<&str> = Some("storage.modifier.calru");
+const CONTROL: Option<&str> = Some("keyword.control.calru");
+const CONSTANT: Option<&str> = Some("constant.language.calru");
+const BUILTIN: Option<&str> = Some("support.function.builtin.calru");
+const ASSIGNMENT: Option<&str> = Some("keyword.operator.assignment.calru");
+const COMPARISON: Option<&str> = Some("keyword.operator.comparison.calru");
+const LOGICAL: Option<&str> = Some("keyword.operator.logical.calru");
+const OPERATOR: Option<&str> = Some("keyword.operator.calru");
+const TERMINATOR: Option<&str> = Some("punctuation.terminator.calru");
+const SEPARATOR: Option<&str> = Some("punctuation.separator.calru");
+
+/// Reserved words. Any other identifier lexes as `TokenType::Identifier`, including the
+/// `TYPE_NAMES`, which the parser resolves in type positions.
+pub const KEYWORDS: &[Word] = &[
+    ("let", TokenType::Let, STORAGE),
+    ("mut", TokenType::Mut, MODIFIER),
+    ("const", TokenType::Const, MODIFIER),
+    ("struct", TokenType::Struct, STORAGE),
+    ("enum", TokenType::Enum, STORAGE),
+    ("if", TokenType::If, CONTROL),
+    ("then", TokenType::Then, CONTROL),
+    ("else", TokenType::Else, CONTROL),
+    ("end", TokenType::End, CONTROL),
+    ("loop", TokenType::Loop, CONTROL),
+    ("break", TokenType::Break, CONTROL),
+    ("match", TokenType::Match, CONTROL),
+    ("try", TokenType::Try, CONTROL),
+    ("catch", TokenType::Catch, CONTROL),
+    ("throw", TokenType::Throw, CONTROL),
+    ("test", TokenType::Test, CONTROL),
+    ("assert", TokenType::Assert, BUILTIN),
+    ("assert_eq", TokenType::AssertEq, BUILTIN),
+    ("true", TokenType::Boolean, CONSTANT),
+    ("false", TokenType::Boolean, CONSTANT),
+    ("none", TokenType::None, CONSTANT),
+    ("stdout", TokenType::Print, BUILTIN),
+    ("print", TokenType::PrintInline, BUILTIN),
+    ("stderr", TokenType::PrintError, BUILTIN),
+    ("fetch", TokenType::Fetch, BUILTIN),
+    ("push", TokenType::Push, BUILTIN),
+    ("pop", TokenType::Pop, BUILTIN),
+    ("len", TokenType::Len, BUILTIN),
+    ("stdin_line", TokenType::StdinLine, BUILTIN),
+    ("stdin_int", TokenType::StdinInt, BUILTIN),
+    ("stdin_float", TokenType::StdinFloat, BUILTIN),
+    ("read_file", TokenType::ReadFile, BUILTIN),
+    ("read_lines", TokenType::ReadLines, BUILTIN),
+    ("write_file", TokenType::WriteFile, BUILTIN),
+];
+
+/// Operators and punctuation. The longest symbol wins.
+pub const SYMBOLS: &[Word] = &[
+    (":=", TokenType::Assign, ASSIGNMENT),
+    ("+=", TokenType::CompoundAssign, ASSIGNMENT),
+    ("-=", TokenType::CompoundAssign, ASSIGNMENT),
+    ("*=", TokenType::CompoundAssign, ASSIGNMENT),
+    ("/=", TokenType::CompoundAssign, ASSIGNMENT),
+    ("%=", TokenType::CompoundAssign, ASSIGNMENT),
+    ("==", TokenType::Equal, COMPARISON),
+    ("!=", TokenType::NotEqual, COMPARISON),
+    (">=", TokenType::GreaterThanOrEqual, COMPARISON),
+    ("<=", TokenType::LessThanOrEqual, COMPARISON),
+    (">", TokenType::GreaterThan, COMPARISON),
+    ("<", TokenType::LessThan, COMPARISON),
+    ("&&", TokenType::And, LOGICAL),
+    ("||", TokenType::Or, LOGICAL),
+    ("=>", TokenType::FatArrow, OPERATOR),
+    ("+", TokenType::Operator, OPERATOR),
+    ("-", TokenType::Operator, OPERATOR),
+    ("*", TokenType::Operator, OPERATOR),
+    ("/", TokenType::Operator, OPERATOR),
+    ("%", TokenType::Operator, OPERATOR),
+    ("!", TokenType::Operator, OPERATOR),
+    (":", TokenType::Colon, None),
+    ("{", TokenType::LeftBrace, None),
+    ("}", TokenType::RightBrace, None),
+    ("(", TokenType::LeftParen, None),
+    (")", TokenType::RightParen, None),
+    ("[", TokenType::LeftBracket, None),
+    ("]", TokenType::RightBracket, None),
+    (";", TokenType::Termination, TERMINATOR),
+    (",", TokenType::Comma, SEPARATOR),
+    (".", TokenType::Dot, None),
+    ("?", TokenType::Question, OPERATOR),
+];
+
+/// Built-in type names. They are identifiers to the lexer and only highlighted as types.
+pub const TYPE_NAMES: &[&str] = &["int", "float", "bool", "string", "option", "error"];
+
+/// A kind of token described by a regular expression rather than fixed text.
+pub struct TokenClass {
+    pub pattern: &'static str,
+    /// `None` for text the lexer skips, like comments.
+    pub token_type: Option<TokenType>,
+    /// The TextMate scope editors highlight it with, if any.
+    pub scope: Option<&'static str>,
+}
+
+/// Bytes that separate tokens and are otherwise skipped, ahead of matching any pattern. A
+/// newline may also end a statement; see `Lexer::with_semicolon_insertion`.
+pub const BLANKS: &[u8] = b" \t\n";
+
+/// Token classes, tried in order before `SYMBOLS`; identifiers, the most common, come
+/// early. Matches are checked further by the lexer, which reports malformed numbers and strings.
+pub const TOKEN_CLASSES: &[TokenClass] = &[
+    // Looked up in `KEYWORDS` once matched.
+    TokenClass {
+        pattern: r"[_\p{XID_Start}]\p{XID_Continue}*",
+        token_type: Some(TokenType::Identifier),
+        scope: Some("variable.other.calru"),
+    },
+    // `///` starts a doc comment; `////` and longer runs are plain comments.
+    TokenClass {
+        pattern: r"///(?:[^/\n][^\n]*)?(?m:$)",
+        token_type: Some(TokenType::DocComment),
+        scope: Some("comment.line.documentation.calru"),
+    },
+    TokenClass { pattern: r"//[^\n]*", token_type: None, scope: Some("comment.line.double-slash.calru") },
+    // Also matches an unterminated literal, so the lexer can report it.
+    TokenClass {
+        pattern: r#""(?:[^"\\]|\\[\s\S])*"?"#,
+        token_type: Some(TokenType::StringLiteral),
+        scope: Some("string.quoted.double.calru"),
+    },
+    // An exponent without digits is matched so that the lexer can report it.
+    TokenClass {
+        pattern: r"(?:[0-9][0-9_]*)?\.[0-9][0-9_]*(?:[eE][+-]?(?:[0-9][0-9_]*)?)?|[0-9][0-9_]*[eE][+-]?(?:[0-9][0-9_]*)?",
+        token_type: Some(TokenType::FloatNumber),
+        scope: Some("constant.numeric.float.calru"),
+    },
+    TokenClass {
+        pattern: r"0[xXoObB][0-9A-Za-z_]*|[0-9][0-9_]*",
+        token_type: Some(TokenType::Number),
+        scope: Some("constant.numeric.integer.calru"),
+    },
+];
+
+/// Block comments nest, which a regular expression cannot match, so the lexer scans them.
+pub const BLOCK_COMMENT: (&str, &str) = ("/*", "*/");
+
+/// The regular expression matching any of `SYMBOLS`, longest first.
+pub fn symbol_pattern<'a>(symbols: impl Iterator<Item = &'a str>) -> String {
+    let mut symbols: Vec<&str> = symbols.collect();
+    symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+    let alternatives: Vec<String> = symbols.iter().map(|symbol| regex::escape(symbol)).collect();
+    alternatives.join("|")
+}
+
+/// Groups `entries` by scope, keeping the order in which scopes first appear.
+fn group_by_scope(entries: &[Word]) -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut groups: Vec<(&'static str, Vec<&'static str>)> = Vec::new();
+    for (text, _, scope) in entries {
+        let Some(scope) = *scope else { continue };
+        match groups.iter_mut().find(|(name, _)| *name == scope) {
+            Some((_, texts)) => texts.push(text),
+            None => groups.push((scope, vec![text])),
+        }
+    }
+    groups
+}
+
+fn json_string(text: &str) -> String {
+    let mut json = String::from("\"");
+    for ch in text.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\t' => json.push_str("\\t"),
+            _ => json.push(ch),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// The grammar's match rules as (scope, pattern) pairs. An editor takes the rule whose match
+/// starts first, the earlier rule on a tie, so keywords and type names come before the
+/// identifier rule that also matches them. Identifiers need a rule of their own, or the `1`
+/// in `variable1` would be highlighted as a number.
+pub fn highlighting_rules() -> Vec<(&'static str, String)> {
+    let mut rules = Vec::new();
+    for (scope, words) in group_by_scope(KEYWORDS) {
+        rules.push((scope, format!(r"\b(?:{})\b", words.join("|"))));
+    }
+    rules.push(("storage.type.primitive.calru", format!(r"\b(?:{})\b", TYPE_NAMES.join("|"))));
+    for class in TOKEN_CLASSES {
+        if let Some(scope) = class.scope {
+            rules.push((scope, class.pattern.to_string()));
+        }
+    }
+    for (scope, symbols) in group_by_scope(SYMBOLS) {
+        rules.push((scope, symbol_pattern(symbols.into_iter())));
+    }
+    rules
+}
+
+/// Exports the token specification as a TextMate grammar (`calru.tmLanguage.json`), the
+/// format VS Code, Sublime Text and most other editors highlight with.
+pub fn highlighting_grammar() -> String {
+    let mut patterns = vec![r##"{ "include": "#block-comment" }"##.to_string()];
+    for (scope, pattern) in highlighting_rules() {
+        patterns.push(format!("{{ \"name\": {}, \"match\": {} }}", json_string(scope), json_string(&pattern)));
+    }
+
+    let (begin, end) = BLOCK_COMMENT;
+    format!(
+        "{{\n  \"name\": \"calru\",\n  \"scopeName\": \"source.calru\",\n  \"fileTypes\": [\"cru\"],\n  \"patterns\": [\n    {}\n  ],\n  \"repository\": {{\n    \"block-comment\": {{ \"name\": \"comment.block.calru\", \"begin\": {}, \"end\": {}, \"patterns\": [{{ \"include\": \"#block-comment\" }}] }}\n  }}\n}}\n",
+        patterns.join(",\n    "),
+        json_string(&regex::escape(begin)),
+        json_string(&regex::escape(end)),
+    )
+}
@@ -0,0 +1,53 @@
+//! Helpers shared by the integration tests. Each test file uses only some of them.
+#![allow(dead_code)]
+
+use calru::ast::AST;
+use calru::interpreter::Interpreter;
+use calru::lexer::lexer;
+use calru::parser::Parser;
+use calru::symbol_table::{SymbolType, SymbolValue};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// A writer the test keeps a handle to after the interpreter takes ownership of it.
+#[derive(Clone, Default)]
+pub struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl SharedOutput {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).expect("output should be UTF-8")
+    }
+}
+
+impl Write for SharedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Parses `source` into an interpreter, writing to nowhere, and the statements it is to run.
+pub fn interpreter_for(source: &str) -> Result<(Interpreter, Vec<AST>), String> {
+    let tokens = lexer(source)?;
+    let mut parser = Parser::new(tokens).with_source(source);
+    let (asts, symbol_table) = parser.parse_program()?;
+    Ok((Interpreter::new(symbol_table).with_output(io::sink()), asts))
+}
+
+pub fn run(source: &str) -> Result<Interpreter, String> {
+    let (mut interpreter, asts) = interpreter_for(source)?;
+    interpreter.run(asts)?;
+    Ok(interpreter)
+}
+
+pub fn value_of(interpreter: &Interpreter, name: &str) -> SymbolValue {
+    interpreter.symbol_table().lookup(name).expect("variable should exist").value.clone()
+}
+
+pub fn type_of(interpreter: &Interpreter, name: &str) -> SymbolType {
+    interpreter.symbol_table().lookup(name).expect("variable should exist").symbol_type.clone()
+}
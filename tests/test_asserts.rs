@@ -0,0 +1,61 @@
+mod common;
+
+use common::{interpreter_for, SharedOutput};
+
+fn run(source: &str) -> Result<(), String> {
+    let (mut interpreter, asts) = interpreter_for(source)?;
+    interpreter.run(asts)
+}
+
+#[test]
+fn test_assert_failures_report_values_and_span() {
+    assert!(run("let x :int := 2;\nassert(x > 1);\nassert_eq(x, 2);").is_ok());
+
+    let err = run("let x :int := 2;\nassert(x > 5);").err().unwrap();
+    assert_eq!(
+        err,
+        "Assertion failed at position Position { line: 2, column: 1 } to Position { line: 2, column: 13 }."
+    );
+
+    let err = run("let x :int := 2;\nassert_eq(x + 1, 4);").err().unwrap();
+    assert_eq!(
+        err,
+        "Assertion failed: left 3 != right 4 at position Position { line: 2, column: 1 } to Position { line: 2, column: 19 }."
+    );
+
+    let err = run("assert_eq(1, 1.0);").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot compare Int and Float in 'assert_eq'"));
+}
+
+#[test]
+fn test_blocks_are_skipped_by_normal_run() {
+    assert!(run("let x :int := 1;\ntest \"fails\" {\n  assert(x == 2);\n}").is_ok());
+}
+
+#[test]
+fn test_mode_reports_each_block() {
+    let source = "let mut x :int := 1;\ntest \"passes\" {\n  x := x + 1;\n  assert_eq(x, 2);\n}\ntest \"sees fresh state\" {\n  assert_eq(x, 2);\n}";
+    let output = SharedOutput::default();
+    let (interpreter, asts) = interpreter_for(source).expect("program should parse");
+    let mut interpreter = interpreter.with_output(output.clone());
+    let outcomes = interpreter.run_tests(asts).expect("setup should run");
+
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes[0].result.is_ok());
+    assert!(outcomes[1].result.is_err());
+    let report = output.contents();
+    assert!(report.starts_with("test passes ... ok\ntest sees fresh state ... FAILED: Assertion failed: left 1 != right 2"));
+    assert!(report.ends_with("1 passed; 1 failed\n"));
+}
+
+#[test]
+fn test_mode_runs_only_declarations_as_setup() {
+    let source = "let mut x :int := 1;\nconst N := 3;\nx := 5;\nstdout(\"side effect\");\ntest \"sees declarations\" {\n  assert_eq(x + N, 4);\n}";
+    let output = SharedOutput::default();
+    let (interpreter, asts) = interpreter_for(source).expect("program should parse");
+    let mut interpreter = interpreter.with_output(output.clone());
+    let outcomes = interpreter.run_tests(asts).expect("setup should run");
+
+    assert!(outcomes[0].result.is_ok(), "{:?}", outcomes[0].result);
+    assert_eq!(output.contents(), "test sees declarations ... ok\n1 passed; 0 failed\n");
+}
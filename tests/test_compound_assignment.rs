@@ -0,0 +1,58 @@
+mod common;
+
+use calru::ast::{AST, ASTNode};
+use calru::lexer::lexer;
+use calru::parser::Parser;
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+#[test]
+fn test_compound_assignment_on_scalars() {
+    let source = "let mut i := 10;\ni += 5;\ni -= 1;\ni *= 3;\ni /= 4;\ni %= 7;\nlet mut f := 1.5;\nf *= 2.0;\nf %= 2.0;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "i"), SymbolValue::Int(3));
+    assert_eq!(value_of(&interpreter, "f"), SymbolValue::Float(1.0));
+
+    let interpreter = run("let mut i := 0;\nloop {\n  i += 1;\n  if (i == 4) then\n    break;\n  end\n}").expect("program should run");
+    assert_eq!(value_of(&interpreter, "i"), SymbolValue::Int(4));
+}
+
+#[test]
+fn test_compound_assignment_lowering() {
+    let tokens = lexer("let mut i := 1;\ni += 2;").unwrap();
+    let (asts, _) = Parser::new(tokens).parse_program().unwrap();
+
+    assert_eq!(asts[1], AST::new(ASTNode::Assignment {
+        variable: "i".to_string(),
+        expression: Box::new(AST::new(ASTNode::BinaryOperation {
+            operator: "+".to_string(),
+            left: Box::new(AST::new(ASTNode::Identifier("i".to_string()))),
+            right: Box::new(AST::new(ASTNode::Int(2))),
+        })),
+    }));
+}
+
+#[test]
+fn test_list_append() {
+    let interpreter = run("let mut xs :[int] := [];\nxs += 4;\nxs += 2 * 3;").expect("program should run");
+    assert_eq!(value_of(&interpreter, "xs"), SymbolValue::List(vec![SymbolValue::Int(4), SymbolValue::Int(6)]));
+
+    let err = run("let mut xs :[int] := [];\nxs += \"a\";").err().unwrap();
+    assert_eq!(err, "Type mismatch: cannot append value of type String to list 'xs' of type List(Int) at position Position { line: 2, column: 4 }.");
+}
+
+#[test]
+fn test_compound_assignment_errors() {
+    let err = run("let mut i := 1;\ni += 2.0;").err().unwrap();
+    assert_eq!(err, "Type mismatch: cannot perform '+' operation between Int and Float at position Position { line: 2, column: 3 }.");
+
+    let err = run("let mut xs :[int] := [1];\nxs -= 1;").err().unwrap();
+    assert_eq!(err, "Type mismatch: cannot perform '-' operation between List(Int) and Int at position Position { line: 2, column: 4 }.");
+
+    let err = run("let i := 1;\ni += 2;").err().unwrap();
+    assert!(err.starts_with("Cannot assign to immutable variable 'i'"), "{}", err);
+
+    let err = run("let mut i := 1;\ni %= 0;").err().unwrap();
+    assert_eq!(err, "Division by zero.");
+}
@@ -0,0 +1,134 @@
+use calru::cst::{Cst, CstElement, CstNode, NodeKind, TriviaKind};
+use calru::lexer::lexer;
+use calru::models::TokenType;
+use calru::parser::Parser;
+
+const PROGRAM: &str = "/// The answer.
+let mut x :int := 4_2; // trailing
+/* outer /* nested */ still a comment */
+let names := [\"a\\n\", \"\u{3bb}\"];
+if (x == 42) then
+    stdout(\"{x}\", names);
+end
+\t
+";
+
+#[test]
+fn test_cst_round_trips_source() {
+    let sources = [
+        PROGRAM,
+        "",
+        "  // only a comment",
+        "// semicolons: auto\nlet x := 1\nlet y := x\n",
+        "let é := 1 @ 2;",
+        "let s := \"unterminated",
+        "let x := (1 + ;",
+        "/* unterminated",
+    ];
+    for source in sources {
+        let cst = Cst::parse(source);
+        assert_eq!(cst.to_string(), source);
+        assert_eq!(cst.root.span(), 0..source.len());
+    }
+}
+
+#[test]
+fn test_cst_structure_and_trivia() {
+    let cst = Cst::parse(PROGRAM);
+    assert!(cst.errors.is_empty(), "{:?}", cst.errors);
+    assert_eq!(cst.root.kind, NodeKind::Program);
+
+    let statements: Vec<_> = cst.root.nodes().collect();
+    assert_eq!(statements.iter().map(|node| node.kind).collect::<Vec<_>>(), [NodeKind::Let, NodeKind::Let, NodeKind::If]);
+    // The doc comment belongs to the declaration it documents.
+    assert_eq!(statements[0].to_string(), "/// The answer.\nlet mut x :int := 4_2;");
+    assert_eq!(statements[0].nodes().map(|node| node.to_string()).collect::<Vec<_>>(), ["int"]);
+    assert!(matches!(cst.root.children.last(), Some(CstElement::Token(token)) if token.raw.token_type == TokenType::EOF));
+
+    // The if statement holds its condition and its branch, which holds the print statement.
+    let parts: Vec<_> = statements[2].nodes().collect();
+    assert_eq!(parts.iter().map(|node| node.kind).collect::<Vec<_>>(), [NodeKind::Binary, NodeKind::Branch]);
+    assert_eq!(parts[0].to_string(), "x == 42");
+    assert_eq!(parts[1].nodes().map(|node| node.kind).collect::<Vec<_>>(), [NodeKind::Print]);
+
+    let offset = PROGRAM.find("4_2").unwrap();
+    let token = cst.token_at(offset + 1).unwrap();
+    assert_eq!((token.raw.token_type, token.raw.text), (TokenType::Number, "4_2"));
+
+    let let_token = cst.token_at(PROGRAM.find("let names").unwrap()).unwrap();
+    let kinds: Vec<_> = let_token.leading_trivia.iter().map(|trivia| trivia.kind).collect();
+    assert_eq!(
+        kinds,
+        [TriviaKind::Whitespace, TriviaKind::LineComment, TriviaKind::Whitespace, TriviaKind::BlockComment, TriviaKind::Whitespace]
+    );
+    assert_eq!(let_token.leading_trivia[3].text, "/* outer /* nested */ still a comment */");
+}
+
+/// The kinds of `node` and its descendants, each with the text it covers, in source order.
+fn outline(node: &CstNode, outline: &mut Vec<(NodeKind, String)>) {
+    outline.push((node.kind, node.to_string().trim().to_string()));
+    for child in node.nodes() {
+        self::outline(child, outline);
+    }
+}
+
+#[test]
+fn test_cst_nodes_follow_productions() {
+    let source = "enum E { A, B(int) }
+let l := [[1]];
+let n := match E.B(2) {
+  E.A => 0,
+  E.B(v) => (v + 1) * l[0][0]
+};
+loop {
+  break;
+}";
+    let cst = Cst::parse(source);
+    assert!(cst.errors.is_empty(), "{:?}", cst.errors);
+
+    let mut nodes = Vec::new();
+    outline(&cst.root, &mut nodes);
+    let nodes: Vec<_> = nodes.iter().skip(1).map(|(kind, text)| (*kind, text.as_str())).collect();
+    assert_eq!(
+        nodes,
+        [
+            (NodeKind::EnumDecl, "enum E { A, B(int) }"),
+            (NodeKind::Let, "let l := [[1]];"),
+            (NodeKind::List, "[[1]]"),
+            (NodeKind::List, "[1]"),
+            (NodeKind::Let, "let n := match E.B(2) {\n  E.A => 0,\n  E.B(v) => (v + 1) * l[0][0]\n};"),
+            (NodeKind::Match, "match E.B(2) {\n  E.A => 0,\n  E.B(v) => (v + 1) * l[0][0]\n}"),
+            (NodeKind::EnumValue, "E.B(2)"),
+            (NodeKind::MatchArm, "E.A => 0"),
+            (NodeKind::MatchArm, "E.B(v) => (v + 1) * l[0][0]"),
+            (NodeKind::Binary, "(v + 1) * l[0][0]"),
+            (NodeKind::Paren, "(v + 1)"),
+            (NodeKind::Binary, "v + 1"),
+            (NodeKind::Index, "l[0][0]"),
+            (NodeKind::Index, "l[0]"),
+            (NodeKind::Loop, "loop {\n  break;\n}"),
+            (NodeKind::Block, "{\n  break;\n}"),
+            (NodeKind::Break, "break;"),
+        ]
+    );
+}
+
+#[test]
+fn test_cst_derives_ast() {
+    let cst = Cst::parse(PROGRAM);
+    let (asts, _) = cst.to_ast().unwrap();
+    let (expected, _) = Parser::new(lexer(PROGRAM).unwrap()).parse_program().unwrap();
+    assert_eq!(format!("{:?}", asts), format!("{:?}", expected));
+
+    // The statement the parser stopped in is left as loose tokens.
+    let cst = Cst::parse("let x := 1;\nlet y := (1 + ;");
+    assert_eq!(cst.root.nodes().map(|node| node.kind).collect::<Vec<_>>(), [NodeKind::Let]);
+    assert_eq!(cst.to_ast().unwrap_err(), cst.errors.join("\n"));
+
+    // Rejected characters are kept as skipped trivia, and reported.
+    let cst = Cst::parse("let x := 1 @@ ;");
+    assert_eq!(cst.errors.len(), 2, "{:?}", cst.errors);
+    let token = cst.token_at(cst.to_string().find(';').unwrap()).unwrap();
+    assert_eq!(token.leading_trivia[1].kind, TriviaKind::Skipped);
+    assert_eq!(token.leading_trivia[1].text, "@@");
+}
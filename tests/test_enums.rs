@@ -0,0 +1,75 @@
+mod common;
+
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+const SHAPE: &str = "enum Shape { Circle(float), Rect(float, float), Empty }\n";
+
+#[test]
+fn test_enum_values_and_match_bindings() {
+    let source = format!(
+        "{}let s :Shape := Shape.Rect(2.0, 3.0);\nlet area :float := match s {{\n  Shape.Circle(r) => 3.0 * r * r,\n  Shape.Rect(w, h) => w * h,\n  Shape.Empty => 0.0,\n}};",
+        SHAPE
+    );
+    let interpreter = run(&source).expect("program should run");
+
+    assert_eq!(
+        value_of(&interpreter, "s"),
+        SymbolValue::Enum {
+            name: "Shape".to_string(),
+            variant: "Rect".to_string(),
+            values: vec![SymbolValue::Float(2.0), SymbolValue::Float(3.0)],
+        }
+    );
+    assert_eq!(value_of(&interpreter, "area"), SymbolValue::Float(6.0));
+}
+
+#[test]
+fn test_match_wildcard_covers_remaining_variants() {
+    let source = format!(
+        "{}let s :Shape := Shape.Empty;\nlet empty :bool := match s {{ Shape.Empty => true, _ => false }};",
+        SHAPE
+    );
+    let interpreter = run(&source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "empty"), SymbolValue::Boolean(true));
+}
+
+#[test]
+fn test_non_exhaustive_match_lists_missing_variants() {
+    let source = format!("{}let s :Shape := Shape.Empty;\nlet n :int := match s {{ Shape.Circle(r) => 1 }};", SHAPE);
+    let err = run(&source).err().unwrap();
+
+    assert_eq!(err, "Non-exhaustive match on 'Shape': missing variant(s) Rect, Empty at position Position { line: 3, column: 15 }.");
+}
+
+#[test]
+fn test_enum_and_match_errors() {
+    let err = run(&format!("{}let s :Shape := Shape.Circle(1);", SHAPE)).err().unwrap();
+    assert_eq!(
+        err,
+        "Type mismatch: variant 'Shape.Circle' expects a value of type Float, found Int at position Position { line: 2, column: 23 }."
+    );
+
+    let err = run(&format!("{}let s :Shape := Shape.Rect(1.0);", SHAPE)).err().unwrap();
+    assert_eq!(err, "Variant 'Shape.Rect' expects 2 value(s), found 1 at position Position { line: 2, column: 23 }.");
+
+    let err = run(&format!("{}let s :Shape := Shape.Empty;\nlet n :int := match s {{ Shape.Empty => 1, _ => 2.0 }};", SHAPE))
+        .err()
+        .unwrap();
+    assert_eq!(err, "Type mismatch: match arm '_' has type Float, expected Int at position Position { line: 3, column: 43 }.");
+
+    let err = run(&format!("{}let s :Shape := Shape.Empty;\nlet n :int := match s {{ _ => 1, Shape.Empty => 2 }};", SHAPE))
+        .err()
+        .unwrap();
+    assert_eq!(err, "Unreachable match arm 'Empty' at position Position { line: 3, column: 33 }.");
+
+    let err = run(&format!("{}let s :Shape := Shape.Empty;\nlet n :int := match 1 {{ _ => 1 }};", SHAPE)).err().unwrap();
+    assert_eq!(err, "Type mismatch: match can only be performed on enums, found Int at position Position { line: 3, column: 21 }.");
+
+    let err = run("enum Dup { A, B, A }").err().unwrap();
+    assert_eq!(err, "Variant 'A' already declared in enum 'Dup' at position Position { line: 1, column: 18 }.");
+
+    let err = run("enum Empty { }").err().unwrap();
+    assert_eq!(err, "Enum 'Empty' must declare at least one variant at position Position { line: 1, column: 6 }.");
+}
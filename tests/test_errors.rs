@@ -0,0 +1,36 @@
+mod common;
+
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+#[test]
+fn test_catch_runtime_failure() {
+    let source = "let mut l :[int] := [1];\nlet mut message :string := \"\";\nlet mut line :int := 0;\ntry {\n  stdout(\"before\");\n  l.pop();\n  l.pop();\n} catch (e) {\n  message := e.message;\n  line := e.line;\n}";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "message"), SymbolValue::String("List 'l' is empty".to_string()));
+    assert_eq!(value_of(&interpreter, "line"), SymbolValue::Int(7));
+}
+
+#[test]
+fn test_throw_and_rethrow() {
+    let source = "let mut column :int := 0;\nlet mut message :string := \"\";\ntry {\n  try {\n    throw \"bad input\";\n  } catch (e) {\n    throw e;\n  }\n} catch (outer) {\n  column := outer.column;\n  message := outer.message;\n}";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "column"), SymbolValue::Int(5));
+    assert_eq!(value_of(&interpreter, "message"), SymbolValue::String("bad input".to_string()));
+    assert!(interpreter.symbol_table().lookup("outer").is_none());
+}
+
+#[test]
+fn test_uncaught_throw_and_break() {
+    let err = run("throw \"stop\";").err().unwrap();
+    assert_eq!(err, "stop at line 1, column 1");
+
+    let source = "let mut i :int := 0;\nloop {\n  try {\n    i := i + 1;\n    break;\n  } catch (e) {\n    i := 100;\n  }\n}";
+    let interpreter = run(source).expect("program should run");
+    assert_eq!(value_of(&interpreter, "i"), SymbolValue::Int(1));
+
+    let err = run("throw 3;").err().unwrap();
+    assert!(err.starts_with("Type mismatch: throw expects a String or an Error, found Int"));
+}
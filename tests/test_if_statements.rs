@@ -1,11 +1,10 @@
 #[cfg(test)]
 mod tests {
-    use super::*;
     use calru::parser::Parser;
     use calru::models::{TokenType, Token, Position};
     use calru::ast::ASTNode;
-    use calru::symbol_table::SymbolTable;
     use calru::ast::AST;
+    use calru::symbol_table::{Mutability, SymbolType};
 
     fn create_tokens(tokens: Vec<(&str, TokenType)>) -> Vec<Token> {
         tokens.into_iter()
@@ -14,6 +13,7 @@ mod tests {
                 token_type,
                 value: value.to_string(),
                 position: Position { line: 1, column: i + 1 },
+                span: 0..0,
             })
             .collect()
     }
@@ -23,7 +23,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
@@ -31,8 +32,10 @@ mod tests {
 
         let mut parser = Parser::new(tokens);
 
-        let expected_ast = AST::new(ASTNode::Assignment {
+        let expected_ast = AST::new(ASTNode::Let {
             variable: "variable1".to_string(),
+            symbol_type: SymbolType::Int,
+            mutability: Mutability::Immutable,
             expression: Box::new(AST::new(ASTNode::Int(1))),
         });
 
@@ -47,25 +50,26 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("2", TokenType::Number),
             (";", TokenType::Termination),
         ]);
 
         let mut parser = Parser::new(tokens);
-        let mut symbol_table = SymbolTable::new();
 
         parser.parse_statement().unwrap();
 
         let err = parser.parse_statement().err().unwrap();
-        assert_eq!(err, "Variable 'variable1' already declared at position Position { line: 1, column: 1 }.");
+        assert_eq!(err, "Variable 'variable1' already declared at position Position { line: 1, column: 9 }.");
     }
 
     #[test]
@@ -73,17 +77,17 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1.5", TokenType::FloatNumber),
             (";", TokenType::Termination),
         ]);
 
         let mut parser = Parser::new(tokens);
-        let mut symbol_table = SymbolTable::new();
 
         let err = parser.parse_statement().err().unwrap();
-        assert_eq!(err, "Type mismatch: cannot assign expression of type Float to variable of type Int at position Position { line: 1, column: 1 }.");
+        assert_eq!(err, "Type mismatch: cannot assign expression of type Float to variable of type Int at position Position { line: 1, column: 2 }.");
     }
 
     #[test]
@@ -91,7 +95,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
         ]);
@@ -107,7 +112,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             ("+", TokenType::Operator),
@@ -117,8 +123,10 @@ mod tests {
 
         let mut parser = Parser::new(tokens);
 
-        let expected_ast = AST::new(ASTNode::Assignment {
+        let expected_ast = AST::new(ASTNode::Let {
             variable: "variable1".to_string(),
+            symbol_type: SymbolType::Int,
+            mutability: Mutability::Immutable,
             expression: Box::new(AST::new(ASTNode::BinaryOperation {
                 operator: "+".to_string(),
                 left: Box::new(AST::new(ASTNode::Int(1))),
@@ -142,7 +150,8 @@ mod tests {
             ("then", TokenType::Then),
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
@@ -153,8 +162,10 @@ mod tests {
 
         let expected_ast = AST::new(ASTNode::If {
             condition: Box::new(AST::new(ASTNode::Boolean(true))),
-            then_branch: Box::new(AST::new(ASTNode::Assignment {
+            then_branch: Box::new(AST::new(ASTNode::Let {
                 variable: "variable1".to_string(),
+                symbol_type: SymbolType::Int,
+                mutability: Mutability::Immutable,
                 expression: Box::new(AST::new(ASTNode::Int(1))),
             })),
             else_branch: None,
@@ -178,14 +189,16 @@ mod tests {
             ("then", TokenType::Then),
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
             ("else", TokenType::Else),
             ("let", TokenType::Let),
             ("variable2", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("2", TokenType::Number),
             (";", TokenType::Termination),
@@ -196,12 +209,16 @@ mod tests {
 
         let expected_ast = AST::new(ASTNode::If {
             condition: Box::new(AST::new(ASTNode::Boolean(false))),
-            then_branch: Box::new(AST::new(ASTNode::Assignment {
+            then_branch: Box::new(AST::new(ASTNode::Let {
                 variable: "variable1".to_string(),
+                symbol_type: SymbolType::Int,
+                mutability: Mutability::Immutable,
                 expression: Box::new(AST::new(ASTNode::Int(1))),
             })),
-            else_branch: Some(Box::new(AST::new(ASTNode::Assignment {
+            else_branch: Some(Box::new(AST::new(ASTNode::Let {
                 variable: "variable2".to_string(),
+                symbol_type: SymbolType::Int,
+                mutability: Mutability::Immutable,
                 expression: Box::new(AST::new(ASTNode::Int(2))),
             }))),
         });
@@ -0,0 +1,47 @@
+mod common;
+
+use calru::symbol_table::{SymbolType, SymbolValue};
+use common::{run, type_of};
+
+#[test]
+fn test_let_infers_type_from_expression() {
+    let source = "let x := 1 + 2;\nlet f := 2.5;\nlet l := [\"a\", \"b\"];\nlet mut m := {1: true};\nlet t := (x, f);\nconst N := x;";
+    let err = run(source).err().unwrap();
+    assert!(err.starts_with("Constant 'N' must be initialized with a constant expression"));
+
+    let interpreter = run("let x := 1 + 2;\nlet f := 2.5;\nlet l := [\"a\", \"b\"];\nlet t := (x, f);\nconst N := 4;").expect("program should run");
+    assert_eq!(type_of(&interpreter, "x"), SymbolType::Int);
+    assert_eq!(type_of(&interpreter, "f"), SymbolType::Float);
+    assert_eq!(type_of(&interpreter, "l"), SymbolType::List(Box::new(SymbolType::String)));
+    assert_eq!(type_of(&interpreter, "t"), SymbolType::Tuple(vec![SymbolType::Int, SymbolType::Float]));
+    assert_eq!(type_of(&interpreter, "N"), SymbolType::Int);
+}
+
+#[test]
+fn test_empty_collections_need_annotation() {
+    let err = run("let l := [];").err().unwrap();
+    assert_eq!(
+        err,
+        "Cannot infer the type of 'l' from '[]' at position Position { line: 1, column: 5 }. Add a type annotation."
+    );
+
+    let err = run("let m := {};").err().unwrap();
+    assert!(err.starts_with("Cannot infer the type of 'm' from '{}'"), "{}", err);
+
+    // Other inference failures are reported as they are; an annotation would not fix them.
+    let err = run("let m := {\"a\": 1};\nlet y := m[1];").err().unwrap();
+    assert_eq!(err, "Type mismatch: map key must be of type String, found Int.");
+
+    let err = run("let o := none;").err().unwrap();
+    assert!(err.starts_with("Cannot infer the type of 'o' from 'none'"));
+
+    let interpreter = run("let mut l :[int] := [];\nl.push(3);\nlet m :{string: int} := {};\nl := [];").expect("program should run");
+    assert_eq!(interpreter.symbol_table().lookup("l").unwrap().value, SymbolValue::List(vec![]));
+    assert_eq!(interpreter.symbol_table().lookup("m").unwrap().value, SymbolValue::Map(vec![]));
+}
+
+#[test]
+fn test_annotation_is_checked_against_inferred_type() {
+    let err = run("let x :float := 1;").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Int to variable of type Float"));
+}
@@ -0,0 +1,251 @@
+mod common;
+
+use calru::interpreter::Interpreter;
+use calru::symbol_table::SymbolValue;
+use common::{interpreter_for, value_of, SharedOutput};
+use std::io::Cursor;
+
+fn run_with_input(source: &str, input: &str) -> Result<Interpreter, String> {
+    run(source, input, SharedOutput::default(), SharedOutput::default())
+}
+
+fn run_and_capture(source: &str) -> Result<String, String> {
+    let output = SharedOutput::default();
+    run(source, "", output.clone(), SharedOutput::default())?;
+    Ok(output.contents())
+}
+
+fn run(source: &str, input: &str, output: SharedOutput, error_output: SharedOutput) -> Result<Interpreter, String> {
+    let (interpreter, asts) = interpreter_for(source)?;
+    let mut interpreter = interpreter
+        .with_input(Cursor::new(input.to_string()))
+        .with_output(output)
+        .with_error_output(error_output);
+    interpreter.run(asts)?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_stdin_typed_values() {
+    let source = "let name :string := stdin_line();\nlet count :int := stdin_int();\nlet ratio :float := stdin_float();";
+    let interpreter = run_with_input(source, "calru\n 42 \n2.5\n").expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "name"), SymbolValue::String("calru".to_string()));
+    assert_eq!(value_of(&interpreter, "count"), SymbolValue::Int(42));
+    assert_eq!(value_of(&interpreter, "ratio"), SymbolValue::Float(2.5));
+}
+
+#[test]
+fn test_stdin_in_expression() {
+    let source = "let total :int := stdin_int() + stdin_int();";
+    let interpreter = run_with_input(source, "3\n4\n").expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "total"), SymbolValue::Int(7));
+}
+
+#[test]
+fn test_stdin_int_parse_error() {
+    let source = "let count :int := stdin_int();";
+    let err = run_with_input(source, "abc\n").err().unwrap();
+
+    assert_eq!(err, "Invalid integer input 'abc'.");
+}
+
+#[test]
+fn test_stdin_end_of_input() {
+    let source = "let name :string := stdin_line();";
+    let err = run_with_input(source, "").err().unwrap();
+
+    assert_eq!(err, "Unexpected end of input.");
+}
+
+#[test]
+fn test_stdin_type_mismatch() {
+    let source = "let count :int := stdin_line();";
+    let err = run_with_input(source, "1\n").err().unwrap();
+
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type String to variable of type Int"));
+}
+
+#[test]
+fn test_stdout_uses_display_format() {
+    let source = "let n :int := 3;\nlet f :float := 2.5;\nlet l :[int] := [1, 2];\nstdout(n);\nstdout(f);\nstdout(l);\nstdout(\"done\");";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "3\n2.5\n[1, 2]\ndone\n");
+}
+
+#[test]
+fn test_loop_output_is_captured() {
+    let source = "let mut i :int := 0;\nloop {\n  i := i + 1;\n  stdout(i);\n  if (i == 3) then\n    break;\n  end\n}";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "1\n2\n3\n");
+}
+
+#[test]
+fn test_echo_input_to_output() {
+    let output = SharedOutput::default();
+    run("let line :string := stdin_line();\nstdout(line);", "hello\n", output.clone(), SharedOutput::default())
+        .expect("program should run");
+
+    assert_eq!(output.contents(), "hello\n");
+}
+
+#[test]
+fn test_string_interpolation() {
+    let source = "let x :int := 7;\nlet total :float := 10.0;\nlet n :float := 3.0;\nstdout(\"x = {x}, avg = {total / n:.2}\");";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "x = 7, avg = 3.33\n");
+}
+
+#[test]
+fn test_interpolation_width_and_lists() {
+    let source = "let l :[float] := [1.0, 2.5];\nlet name :string := \"ab\";\nstdout(\"[{name:>4}|{name:<4}|{name:^6}] {l:.1} {{literal}}\");";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "[  ab|ab  |  ab  ] [1.0, 2.5] {literal}\n");
+}
+
+#[test]
+fn test_interpolation_unknown_variable_reports_span() {
+    let err = run_and_capture("let x :int := 1;\nstdout(\"x = {x}, y = {y + 1}\");").err().unwrap();
+
+    assert_eq!(
+        err,
+        "Undefined variable: y in placeholder '{y + 1}' at position Position { line: 2, column: 22 } to Position { line: 2, column: 28 }."
+    );
+}
+
+#[test]
+fn test_interpolation_span_counts_escapes_as_written() {
+    let err = run_and_capture("stdout(\"a\\n{y}\");").err().unwrap();
+    assert!(err.ends_with("at position Position { line: 1, column: 12 } to Position { line: 1, column: 14 }."), "{}", err);
+
+    let err = run_and_capture("stdout(\"\\\"\\\"\\\"{y}\");").err().unwrap();
+    assert!(err.ends_with("at position Position { line: 1, column: 15 } to Position { line: 1, column: 17 }."), "{}", err);
+
+    // A line break written into the literal still moves to the next line.
+    let err = run_and_capture("stdout(\"a\n {y}\");").err().unwrap();
+    assert!(err.ends_with("at position Position { line: 2, column: 2 } to Position { line: 2, column: 4 }."), "{}", err);
+}
+
+#[test]
+fn test_interpolation_precision_requires_float() {
+    let err = run_and_capture("let x :int := 1;\nstdout(\"{x:.2}\");").err().unwrap();
+
+    assert!(err.starts_with("Precision can only be applied to float values or lists of floats, found Int"));
+}
+
+#[test]
+fn test_stdout_multiple_arguments() {
+    let source = "let a :int := 1;\nlet b :float := 2.5;\nstdout(a, b, \"c\");\nstdout();";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "1, 2.5, c\n\n");
+}
+
+#[test]
+fn test_print_separator() {
+    let source = "let row :[int] := [1, 2];\nlet sep :int := 0;\nstdout(\"a\", row[0], 2.5, sep: \",\");\nprint(1, 2, sep: \"\");\nstderr(\"x\", sep: \";\");\nstdout(sep, sep: \"-\");";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "a,1,2.5\n120\n");
+
+    let err = run_and_capture("stdout(1, 2, sep: 0);").err().unwrap();
+    assert!(err.starts_with("Type mismatch: 'stdout' expects a 'sep:' of type String, found Int"), "{}", err);
+
+    let err = run_and_capture("stdout(1, sep: \",\", 2);").err().unwrap();
+    assert!(err.starts_with("Expected ')' after the 'sep:' argument"), "{}", err);
+}
+
+#[test]
+fn test_print_without_newline() {
+    let source = "print(\"a\");\nprint(1, 2);\nstdout(\"!\");";
+    let output = run_and_capture(source).expect("program should run");
+
+    assert_eq!(output, "a1, 2!\n");
+}
+
+#[test]
+fn test_stderr_is_separate_from_stdout() {
+    let output = SharedOutput::default();
+    let errors = SharedOutput::default();
+    run("stdout(\"result\");\nstderr(\"warning:\", 3);", "", output.clone(), errors.clone())
+        .expect("program should run");
+
+    assert_eq!(output.contents(), "result\n");
+    assert_eq!(errors.contents(), "warning:, 3\n");
+}
+
+fn temp_root(name: &str) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("calru-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("should create temp dir");
+    root
+}
+
+fn run_in_root(source: &str, root: &std::path::Path) -> Result<Interpreter, String> {
+    let (interpreter, asts) = interpreter_for(source)?;
+    let mut interpreter = interpreter.with_file_root(root);
+    interpreter.run(asts)?;
+    Ok(interpreter)
+}
+
+#[test]
+fn test_file_builtins_inside_root() {
+    let root = temp_root("files");
+    std::fs::write(root.join("data.txt"), "3\n4\n").unwrap();
+
+    let source = "let text :string := read_file(\"data.txt\");\nlet lines :[string] := read_lines(\"data.txt\");\nwrite_file(\"out.txt\", \"{lines}\");";
+    let interpreter = run_in_root(source, &root).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "text"), SymbolValue::String("3\n4\n".to_string()));
+    assert_eq!(
+        value_of(&interpreter, "lines"),
+        SymbolValue::List(vec![SymbolValue::String("3".to_string()), SymbolValue::String("4".to_string())])
+    );
+    assert_eq!(std::fs::read_to_string(root.join("out.txt")).unwrap(), "[3, 4]");
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_file_paths_outside_root_are_rejected() {
+    let root = temp_root("escape");
+
+    let err = run_in_root("write_file(\"../escaped.txt\", \"x\");", &root).err().unwrap();
+    assert_eq!(err, "Path '../escaped.txt' is outside the allowed directory.");
+
+    let err = run_in_root("let s :string := read_file(\"/etc/hostname\");", &root).err().unwrap();
+    assert_eq!(err, "Path '/etc/hostname' is outside the allowed directory.");
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dangling_symlink_cannot_escape_root() {
+    let root = temp_root("dangling");
+    let outside = temp_root("dangling-target");
+    std::os::unix::fs::symlink(outside.join("pwned.txt"), root.join("dangle.txt")).unwrap();
+
+    let err = run_in_root("write_file(\"dangle.txt\", \"pwned\");", &root).err().unwrap();
+    assert_eq!(err, "Path 'dangle.txt' is outside the allowed directory.");
+    assert!(!outside.join("pwned.txt").exists());
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_dir_all(&outside).unwrap();
+}
+
+#[test]
+fn test_file_io_disabled_by_default() {
+    let err = run_and_capture("let s :string := read_file(\"data.txt\");").err().unwrap();
+
+    assert_eq!(err, "File I/O is disabled, cannot access 'data.txt'.");
+}
+
+#[test]
+fn test_file_builtins_are_type_checked() {
+    let err = run_and_capture("write_file(\"out.txt\", 3);").err().unwrap();
+
+    assert!(err.starts_with("Type mismatch: 'write_file' expects an argument of type String, found Int"));
+}
@@ -1,5 +1,5 @@
-use calru::lexer::lexer;
-use calru::models::{TokenType};
+use calru::lexer::{float_literal_value, int_literal_value, lexer, Lexer};
+use calru::models::{Position, Token, TokenType};
 
 #[test]
 fn test_lexer() {
@@ -7,10 +7,11 @@ fn test_lexer() {
     let tokens = lexer(input).expect("Failed to lex input");
     assert_eq!(tokens[0].token_type, TokenType::Let);
     assert_eq!(tokens[1].token_type, TokenType::Identifier);
-    assert_eq!(tokens[2].token_type, TokenType::IntType);
-    assert_eq!(tokens[3].token_type, TokenType::Assign);
-    assert_eq!(tokens[4].token_type, TokenType::FloatNumber);
-    assert_eq!(tokens[5].token_type, TokenType::Termination);
+    assert_eq!(tokens[2].token_type, TokenType::Colon);
+    assert_eq!(tokens[3].token_type, TokenType::Identifier);
+    assert_eq!(tokens[4].token_type, TokenType::Assign);
+    assert_eq!(tokens[5].token_type, TokenType::FloatNumber);
+    assert_eq!(tokens[6].token_type, TokenType::Termination);
 }
 
 #[test]
@@ -41,4 +42,169 @@ fn test_lexer_with_comments() {
     assert_eq!(tokens[8].token_type, TokenType::Number);
     assert_eq!(tokens[9].token_type, TokenType::Termination);
     assert_eq!(tokens[10].token_type, TokenType::EOF);
-}
\ No newline at end of file
+}
+#[test]
+fn test_lexer_with_string_literal() {
+    let input = "let s :string := \"a \\\"b\\\"\\n\";";
+    let tokens = lexer(input).expect("Failed to lex input");
+    assert_eq!(tokens[3].value, "string");
+    assert_eq!(tokens[5].token_type, TokenType::StringLiteral);
+    assert_eq!(tokens[5].value, "a \"b\"\n");
+    assert_eq!(tokens[6].token_type, TokenType::Termination);
+
+    let err = lexer("stdout(\"oops);").err().unwrap();
+    assert_eq!(err, "Unterminated string literal at position Position { line: 1, column: 8 }.");
+}
+
+#[test]
+fn test_lexer_with_compound_assignment() {
+    let tokens = lexer("i += 1; i %= 2; x := a % b;").expect("Failed to lex input");
+    assert_eq!(tokens[1].token_type, TokenType::CompoundAssign);
+    assert_eq!(tokens[1].value, "+=");
+    assert_eq!(tokens[1].position.column, 3);
+    assert_eq!(tokens[5].token_type, TokenType::CompoundAssign);
+    assert_eq!(tokens[5].value, "%=");
+    assert_eq!(tokens[11].token_type, TokenType::Operator);
+    assert_eq!(tokens[11].value, "%");
+}
+
+#[test]
+fn test_lexer_with_block_comments() {
+    let input = "let x := /* outer /* inner */ still\ncomment */ 5;";
+    let tokens = lexer(input).expect("Failed to lex input");
+    assert_eq!(tokens[3].token_type, TokenType::Number);
+    assert_eq!(tokens[3].position.line, 2);
+    assert_eq!(tokens[3].position.column, 12);
+    assert_eq!(tokens[4].token_type, TokenType::Termination);
+
+    let err = lexer("let x := 1;\n  /* open /* nested */ still open").err().unwrap();
+    assert_eq!(err, "Unterminated block comment at position Position { line: 2, column: 3 }.");
+}
+
+#[test]
+fn test_lexer_with_doc_comments() {
+    let input = "/// The answer.\n////  not a doc comment\n//  plain\nlet x := 42;";
+    let tokens = lexer(input).expect("Failed to lex input");
+    assert_eq!(tokens[0].token_type, TokenType::DocComment);
+    assert_eq!(tokens[0].value, "The answer.");
+    assert_eq!(tokens[1].token_type, TokenType::Let);
+}
+
+#[test]
+fn test_lexer_with_numeric_literals() {
+    let tokens = lexer("0xFF 0o17 0b1010 1_000_000 1e-3 + .5 2.5E2").expect("Failed to lex input");
+    let values: Vec<&str> = tokens.iter().map(|token| token.value.as_str()).collect();
+    assert_eq!(values, ["0xFF", "0o17", "0b1010", "1_000_000", "1e-3", "+", ".5", "2.5E2", ""]);
+
+    let ints: Vec<i64> = tokens[..4].iter().map(|token| int_literal_value(&token.value).unwrap()).collect();
+    assert_eq!(ints, [255, 15, 10, 1_000_000]);
+    assert_eq!(tokens[6].token_type, TokenType::FloatNumber);
+    assert_eq!(float_literal_value(&tokens[4].value), Ok(0.001));
+    assert_eq!(float_literal_value(&tokens[6].value), Ok(0.5));
+    assert_eq!(float_literal_value(&tokens[7].value), Ok(250.0));
+
+    // After a dot, digits are tuple indices.
+    let tokens = lexer("t.0.1").expect("Failed to lex input");
+    assert_eq!(tokens[2].token_type, TokenType::Number);
+    assert_eq!(tokens[3].token_type, TokenType::Dot);
+    assert_eq!(tokens[4].value, "1");
+}
+
+#[test]
+fn test_lexer_with_malformed_numbers() {
+    let errors = [
+        ("x := 1.2.3;", "Malformed number literal '1.2.3' at position Position { line: 1, column: 6 }."),
+        ("x := 0b102;", "Invalid digit '2' in binary literal '0b102' at position Position { line: 1, column: 6 }."),
+        ("x := 0x;", "Missing digits in hexadecimal literal '0x' at position Position { line: 1, column: 6 }."),
+        ("x := 1__0;", "Misplaced '_' in number literal '1__0' at position Position { line: 1, column: 6 }."),
+        ("x := 12abc;", "Invalid character 'a' in number literal '12a' at position Position { line: 1, column: 6 }."),
+        ("x := 1e+;", "Missing exponent digits in number literal '1e+' at position Position { line: 1, column: 6 }."),
+        ("x :=\n  9223372036854775808;", "Integer literal '9223372036854775808' is out of range for int at position Position { line: 2, column: 3 }."),
+    ];
+    for (input, expected) in errors {
+        assert_eq!(lexer(input).err().as_deref(), Some(expected), "{}", input);
+    }
+}
+
+#[test]
+fn test_lexer_reports_invalid_characters() {
+    let err = lexer("let x := 1 @ 2;\nlet y := 3 # $;").err().unwrap();
+    assert_eq!(err, "Invalid character '@' at position Position { line: 1, column: 12 }.\n\
+        Invalid character '#' at position Position { line: 2, column: 12 }.\n\
+        Invalid character '$' at position Position { line: 2, column: 14 }.");
+
+    let err = lexer("let y = 3;").err().unwrap();
+    assert_eq!(err, "Unexpected '=' at position Position { line: 1, column: 7 }. Use ':=' for assignment or '==' for comparison.");
+
+    let err = lexer("if (a & b | c) then").err().unwrap();
+    assert_eq!(err, "Unexpected '&' at position Position { line: 1, column: 7 }. Did you mean '&&'?\n\
+        Unexpected '|' at position Position { line: 1, column: 11 }. Did you mean '||'?");
+}
+
+#[test]
+fn test_lexer_byte_spans() {
+    let input = "let größe := \"π≈3\";\n/* ✓ */ stdout(größe); /// note\nlet l :[int] := [0x1F];";
+    let tokens = lexer(input).expect("Failed to lex input");
+    let texts: Vec<&str> = tokens.iter().map(|token| &input[token.span.clone()]).collect();
+    assert_eq!(texts, [
+        "let", "größe", ":=", "\"π≈3\"", ";",
+        "stdout", "(", "größe", ")", ";", "/// note",
+        "let", "l", ":", "[", "int", "]", ":=", "[", "0x1F", "]", ";", "",
+    ]);
+}
+
+#[test]
+fn test_lexer_unicode_identifiers() {
+    let tokens = lexer("let 变量 := 1; let _ñ2 := 变量;").expect("Failed to lex input");
+    assert_eq!(tokens[1].token_type, TokenType::Identifier);
+    assert_eq!(tokens[1].value, "变量");
+    assert_eq!(tokens[6].value, "_ñ2");
+    assert_eq!(tokens[8].value, "变量");
+
+    // Emoji are neither XID_Start nor XID_Continue.
+    let err = lexer("let a😀 := 1;").err().unwrap();
+    assert_eq!(err, "Invalid character '😀' at position Position { line: 1, column: 6 }.");
+}
+
+#[test]
+fn test_position_utf16_column() {
+    let input = "let s := \"😀\"; let x := 1;\nlet é := 2;";
+    let tokens = lexer(input).expect("Failed to lex input");
+    let x = tokens.iter().find(|token| token.value == "x").unwrap();
+    assert_eq!(x.position, Position { line: 1, column: 19 });
+    assert_eq!(x.position.utf16_column(input), 20);
+
+    let two = tokens.iter().find(|token| token.value == "2").unwrap();
+    assert_eq!(two.position.utf16_column(input), two.position.column);
+}
+
+#[test]
+fn test_streaming_lexer_borrows_source() {
+    let source = "/// Greeting.\nlet s := \"plain\" + \"tab\\there\"; @ x";
+    let tokens: Vec<_> = Lexer::new(source).collect();
+
+    let doc = tokens[0].as_ref().unwrap();
+    assert_eq!(doc.token_type, TokenType::DocComment);
+    assert_eq!(doc.text, "/// Greeting.");
+    assert_eq!(doc.value(), "Greeting.");
+
+    let plain = tokens[4].as_ref().unwrap();
+    assert_eq!(plain.text, "\"plain\"");
+    assert!(matches!(plain.value(), std::borrow::Cow::Borrowed("plain")));
+    assert_eq!(&source[plain.span()], "\"plain\"");
+
+    let escaped = tokens[6].as_ref().unwrap();
+    assert!(matches!(escaped.value(), std::borrow::Cow::Owned(ref text) if text == "tab\there"));
+
+    // An invalid character is reported in place and lexing carries on.
+    assert_eq!(tokens[8].as_ref().unwrap_err(), "Invalid character '@' at position Position { line: 2, column: 33 }.");
+    assert_eq!(tokens[9].as_ref().unwrap().text, "x");
+    assert_eq!(tokens[10].as_ref().unwrap().token_type, TokenType::EOF);
+
+    let owned = lexer("let s := \"tab\\there\";").unwrap();
+    let streamed: Vec<Token> = Lexer::new("let s := \"tab\\there\";").map(|token| token.unwrap().into()).collect();
+    assert_eq!(format!("{:?}", owned), format!("{:?}", streamed));
+
+    let err: Vec<_> = Lexer::new("\"open").collect();
+    assert_eq!(err.len(), 1, "a fatal error ends the stream");
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use calru::symbol_table::{SymbolType, SymbolValue};
+use common::{run, value_of};
+
+fn string(s: &str) -> SymbolValue {
+    SymbolValue::String(s.to_string())
+}
+
+#[test]
+fn test_map_literal_and_type() {
+    let interpreter = run("let m :{string: int} := { \"a\": 1, \"b\": 2 };").expect("program should run");
+    let symbol = interpreter.symbol_table().lookup("m").unwrap();
+
+    assert_eq!(symbol.symbol_type, SymbolType::Map(Box::new(SymbolType::String), Box::new(SymbolType::Int)));
+    assert_eq!(symbol.value, SymbolValue::Map(vec![(string("a"), SymbolValue::Int(1)), (string("b"), SymbolValue::Int(2))]));
+}
+
+#[test]
+fn test_map_access_insertion_and_methods() {
+    let source = "let mut m :{string:int} := {\"b\": 2, \"a\": 1};
+m[\"c\"] := 3;
+m[\"a\"] := m[\"a\"] + 10;
+m.remove(\"b\");
+let keys :[string] := m.keys();
+let values :[int] := m.values();
+let has_b :bool := m.contains_key(\"b\");
+let has_c :bool := m.contains_key(\"c\");";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "keys"), SymbolValue::List(vec![string("a"), string("c")]));
+    assert_eq!(value_of(&interpreter, "values"), SymbolValue::List(vec![SymbolValue::Int(11), SymbolValue::Int(3)]));
+    assert_eq!(value_of(&interpreter, "has_b"), SymbolValue::Boolean(false));
+    assert_eq!(value_of(&interpreter, "has_c"), SymbolValue::Boolean(true));
+}
+
+#[test]
+fn test_map_missing_key_is_a_runtime_error() {
+    let err = run("let m :{int: float} := {1: 1.5};\nlet x :float := m[2];").err().unwrap();
+
+    assert_eq!(err, "Key 2 not found in map.");
+}
+
+#[test]
+fn test_map_type_errors() {
+    let err = run("let m :{string: int} := {\"a\": 1};\nlet x :int := m[1];").err().unwrap();
+    assert!(err.starts_with("Type mismatch: map key must be of type String, found Int"));
+
+    // Indexing is checked wherever it appears, not only where a declaration infers a type.
+    let err = run("let m := {\"a\": 1};\nstdout(m[1]);").err().unwrap();
+    assert!(err.starts_with("Type mismatch: map key must be of type String, found Int"), "{}", err);
+
+    let err = run("let l := [1, 2];\nif (l[\"a\"] > 0) then\n  stdout(1);\nend").err().unwrap();
+    assert!(err.starts_with("Type mismatch: index must be of type Int, found String"), "{}", err);
+
+    let err = run("let mut m :{string: int} := {\"a\": 1};\nm[\"b\"] := 2.5;").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Float to element of type Int"));
+
+    let err = run("let m :{string: int} := {\"a\": 1, \"b\": true};").err().unwrap();
+    assert_eq!(err, "Type mismatch in map entries: {String: Int} and {String: Boolean}.");
+
+    let err = run("let m :{float: int} := {1.5: 1};").err().unwrap();
+    assert!(err.starts_with("Map keys must be of type Int, Boolean, or String, found Float"));
+}
+
+#[test]
+fn test_list_indexing() {
+    let interpreter = run("let mut l :[int] := [1, 2, 3];\nl[1] := 20;\nlet x :int := l[1] + l[2];").expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "x"), SymbolValue::Int(23));
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+#[test]
+fn test_constants_are_evaluated_at_compile_time() {
+    let source = "const WIDTH: int := 4;\nconst AREA: int := WIDTH * WIDTH;\nconst LABEL: string := \"area {AREA}\";\nlet x :int := AREA + 1;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "AREA"), SymbolValue::Int(16));
+    assert_eq!(value_of(&interpreter, "LABEL"), SymbolValue::String("area 16".to_string()));
+    assert_eq!(value_of(&interpreter, "x"), SymbolValue::Int(17));
+}
+
+#[test]
+fn test_constant_requires_constant_expression() {
+    let err = run("let x :int := 1;\nconst Y: int := x + 1;").err().unwrap();
+    assert_eq!(err, "Constant 'Y' must be initialized with a constant expression at position Position { line: 2, column: 7 }.");
+
+    let err = run("const Y: int := 1 / 0;").err().unwrap();
+    assert!(err.starts_with("Cannot evaluate constant 'Y' at position Position { line: 1, column: 7 }"));
+}
+
+#[test]
+fn test_let_mut_allows_reassignment() {
+    let interpreter = run("let mut x :int := 1;\nx := x + 1;\nlet mut l :[int] := [1];\nl.push(2);").expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "x"), SymbolValue::Int(2));
+    assert_eq!(value_of(&interpreter, "l"), SymbolValue::List(vec![SymbolValue::Int(1), SymbolValue::Int(2)]));
+}
+
+#[test]
+fn test_modifying_immutable_bindings_notes_declaration() {
+    let err = run("let x :int := 1;\nx := 2;").err().unwrap();
+    assert_eq!(
+        err,
+        "Cannot assign to immutable variable 'x' at position Position { line: 2, column: 1 }. 'x' is declared at position Position { line: 1, column: 5 }; use 'let mut' to make it mutable."
+    );
+
+    let err = run("const N: int := 1;\nN := 2;").err().unwrap();
+    assert!(err.starts_with("Cannot assign to constant 'N' at position Position { line: 2, column: 1 }."));
+
+    let err = run("let l :[int] := [1];\nl.push(2);").err().unwrap();
+    assert!(err.starts_with("Cannot modify immutable variable 'l' at position Position { line: 2, column: 1 }."));
+}
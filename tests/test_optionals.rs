@@ -0,0 +1,57 @@
+mod common;
+
+use calru::symbol_table::{SymbolType, SymbolValue};
+use common::{run, value_of};
+
+#[test]
+fn test_optional_declarations() {
+    let source = "let a :int? := none;\nlet b :option<int> := 3;\nlet missing :bool := a.is_none();\nlet sum :int := a.unwrap_or(1) + b.unwrap_or(0);";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(
+        interpreter.symbol_table().lookup("b").unwrap().symbol_type,
+        SymbolType::Option(Box::new(SymbolType::Int))
+    );
+    assert_eq!(value_of(&interpreter, "a"), SymbolValue::None);
+    assert_eq!(value_of(&interpreter, "missing"), SymbolValue::Boolean(true));
+    assert_eq!(value_of(&interpreter, "sum"), SymbolValue::Int(4));
+}
+
+#[test]
+fn test_try_pop_and_get() {
+    let source = "let mut l :[int] := [7];\nlet first :int? := l.try_pop();\nlet second :int? := l.try_pop();\nlet m :{string: int} := {\"a\": 1};\nlet hit :int? := m.get(\"a\");\nlet miss :int? := m.get(\"b\");\nlet out :int? := l.get(5);";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "first"), SymbolValue::Int(7));
+    assert_eq!(value_of(&interpreter, "second"), SymbolValue::None);
+    assert_eq!(value_of(&interpreter, "hit"), SymbolValue::Int(1));
+    assert_eq!(value_of(&interpreter, "miss"), SymbolValue::None);
+    assert_eq!(value_of(&interpreter, "out"), SymbolValue::None);
+}
+
+#[test]
+fn test_optional_must_be_handled() {
+    let err = run("let a :int? := 1;\nlet b :int := a + 1;").err().unwrap();
+    assert_eq!(
+        err,
+        "Type mismatch: optional value of type Option(Int) must be handled with is_none() or unwrap_or() before '+' at position Position { line: 2, column: 17 }."
+    );
+
+    let err = run("let l := [1, 2];\nlet i := l.get(0);\nstdout(l[i]);").err().unwrap();
+    assert_eq!(
+        err,
+        "Type mismatch: optional value of type Option(Int) must be handled with is_none() or unwrap_or() before '[' at position Position { line: 3, column: 9 }."
+    );
+
+    let err = run("let l := [1, 2];\nif (l.get(0)) then\n  stdout(1);\nend").err().unwrap();
+    assert_eq!(err, "Type mismatch: 'if' condition must be of type Boolean, found Option(Int) at position Position { line: 2, column: 5 }.");
+
+    let err = run("let a :int? := 1;\nlet b :int := a;").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Option(Int) to variable of type Int"));
+
+    let err = run("let a :int := none;").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Option(Void) to variable of type Int"));
+
+    let err = run("let a :int? := 1;\nlet b :float := a.unwrap_or(2.0);").err().unwrap();
+    assert_eq!(err, "Type mismatch: unwrap_or default of type Float does not match Int.");
+}
@@ -1,29 +1,32 @@
 
+use calru::lexer::{lexer, Lexer};
 use calru::parser::Parser;
 use calru::models::{TokenType, Token};
 use calru::ast::{AST, ASTNode};
 use calru::models::Position;
-use calru::symbol_table::SymbolTable; // Import SymbolTable for the tests
+use calru::symbol_table::{Mutability, SymbolType};
 
 #[test]
 fn test_let_decl() {
     let tokens = vec![
-        Token { token_type: TokenType::Let, value: "let".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::Identifier, value: "x".to_string(), position: Position { line: 1, column: 5 } },
-        Token { token_type: TokenType::IntType, value: "int".to_string(), position: Position { line: 1, column: 7 } },
-        Token { token_type: TokenType::Assign, value: ":=".to_string(), position: Position { line: 1, column: 11 } },
-        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 14 } },
-        Token { token_type: TokenType::Termination, value: ";".to_string(), position: Position { line: 1, column: 16 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 17 } },
+        Token { token_type: TokenType::Let, value: "let".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::Identifier, value: "x".to_string(), position: Position { line: 1, column: 5 }, span: 0..0 },
+        Token { token_type: TokenType::Colon, value: ":".to_string(), position: Position { line: 1, column: 7 }, span: 0..0 },
+        Token { token_type: TokenType::Identifier, value: "int".to_string(), position: Position { line: 1, column: 8 }, span: 0..0 },
+        Token { token_type: TokenType::Assign, value: ":=".to_string(), position: Position { line: 1, column: 11 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 14 }, span: 0..0 },
+        Token { token_type: TokenType::Termination, value: ";".to_string(), position: Position { line: 1, column: 16 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 17 }, span: 0..0 },
     ];
 
-    let mut symbol_table = SymbolTable::new(); // Initialize the symbol table
     let mut parser = Parser::new(tokens);
 
     let ast = parser.parse_statement().expect("Failed to parse statement");
 
-    let expected_ast = AST::new(ASTNode::Assignment {
+    let expected_ast = AST::new(ASTNode::Let {
         variable: "x".to_string(),
+        symbol_type: SymbolType::Int,
+        mutability: Mutability::Immutable,
         expression: Box::new(AST::new(ASTNode::Int(42))),
     });
 
@@ -33,10 +36,10 @@ fn test_let_decl() {
 #[test]
 fn test_expression_parsing() {
     let tokens = vec![
-        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 2 } },
-        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 4 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 5 } },
+        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 2 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 4 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 5 }, span: 0..0 },
     ];
 
     let mut parser = Parser::new(tokens);
@@ -54,12 +57,12 @@ fn test_expression_parsing() {
 #[test]
 fn test_expression_with_precedence() {
     let tokens = vec![
-        Token { token_type: TokenType::Number, value: "2".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 2 } },
-        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 4 } },
-        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 5 } },
-        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 6 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 7 } },
+        Token { token_type: TokenType::Number, value: "2".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 2 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 4 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 5 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 6 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 7 }, span: 0..0 },
     ];
 
     let mut parser = Parser::new(tokens);
@@ -81,14 +84,14 @@ fn test_expression_with_precedence() {
 #[test]
 fn test_complex_expression() {
     let tokens = vec![
-        Token { token_type: TokenType::Number, value: "2".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 2 } },
-        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 4 } },
-        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 5 } },
-        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 6 } },
-        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 7 } },
-        Token { token_type: TokenType::Number, value: "5".to_string(), position: Position { line: 1, column: 8 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 9 } },
+        Token { token_type: TokenType::Number, value: "2".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 2 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "3".to_string(), position: Position { line: 1, column: 4 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "+".to_string(), position: Position { line: 1, column: 5 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "4".to_string(), position: Position { line: 1, column: 6 }, span: 0..0 },
+        Token { token_type: TokenType::Operator, value: "*".to_string(), position: Position { line: 1, column: 7 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "5".to_string(), position: Position { line: 1, column: 8 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 9 }, span: 0..0 },
     ];
 
     let mut parser = Parser::new(tokens);
@@ -114,41 +117,109 @@ fn test_complex_expression() {
 #[test]
 fn test_missing_variable() {
     let tokens = vec![
-        Token { token_type: TokenType::Let, value: "let".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::IntType, value: "int".to_string(), position: Position { line: 1, column: 5 } },
-        Token { token_type: TokenType::Assign, value: ":=".to_string(), position: Position { line: 1, column: 8 } },
-        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 11 } },
-        Token { token_type: TokenType::Termination, value: ";".to_string(), position: Position { line: 1, column: 13 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 14 } },
+        Token { token_type: TokenType::Let, value: "let".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::Colon, value: ":".to_string(), position: Position { line: 1, column: 5 }, span: 0..0 },
+        Token { token_type: TokenType::Assign, value: ":=".to_string(), position: Position { line: 1, column: 8 }, span: 0..0 },
+        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 11 }, span: 0..0 },
+        Token { token_type: TokenType::Termination, value: ";".to_string(), position: Position { line: 1, column: 13 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 14 }, span: 0..0 },
     ];
 
     let mut parser = Parser::new(tokens);
-    let mut symbol_table = SymbolTable::new(); // Initialize symbol table
 
     let result = parser.parse_statement();
 
     assert!(result.is_err());
     assert_eq!(
         result.unwrap_err(),
-        "Expected identifier at position Position { line: 1, column: 1 }. Found Some(Token { token_type: IntType, value: \"int\", position: Position { line: 1, column: 5 } })"
+        "Expected identifier at position Position { line: 1, column: 1 }. Found Some(Token { token_type: Colon, value: \":\", position: Position { line: 1, column: 5 }, span: 0..0 })"
     );
 }
 
 #[test]
 fn test_unexpected_token() {
     let tokens = vec![
-        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 1 } },
-        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 3 } },
+        Token { token_type: TokenType::Number, value: "42".to_string(), position: Position { line: 1, column: 1 }, span: 0..0 },
+        Token { token_type: TokenType::EOF, value: "".to_string(), position: Position { line: 1, column: 3 }, span: 0..0 },
     ];
 
     let mut parser = Parser::new(tokens);
-    let mut symbol_table = SymbolTable::new(); // Initialize symbol table
 
     let result = parser.parse_statement();
 
     assert!(result.is_err());
     assert_eq!(
         result.unwrap_err(),
-        "Unexpected token Some(Token { token_type: Number, value: \"42\", position: Position { line: 1, column: 1 } }) at position Position { line: 1, column: 1 }. Expected 'let', 'stdout', 'if', or an identifier."
+        "Unexpected token Some(Token { token_type: Number, value: \"42\", position: Position { line: 1, column: 1 }, span: 0..0 }) at position Position { line: 1, column: 1 }. Expected 'let', 'const', 'struct', 'enum', 'stdout', 'print', 'stderr', 'write_file', 'if', 'loop', 'break', 'try', 'throw', 'assert', 'assert_eq', 'test', or an identifier."
     );
 }
+
+#[test]
+fn test_doc_comments_attach_to_declarations() {
+    let source = "/// Number of retries.\n/// Must be positive.\nconst retries := 3;\n/// A point.\nstruct Point { x: int, y: int }\nlet plain := 1;";
+    let tokens = calru::lexer::lexer(source).expect("Failed to lex input");
+    let (asts, _) = Parser::new(tokens).parse_program().expect("Failed to parse program");
+
+    assert_eq!(asts[0].doc.as_deref(), Some("Number of retries.\nMust be positive."));
+    assert_eq!(asts[1].doc.as_deref(), Some("A point."));
+    assert_eq!(asts[2].doc, None);
+
+}
+
+#[test]
+fn test_unattached_doc_comments_are_plain_comments() {
+    let source = "/// Prints.\nstdout(1);\nloop {\n  /// Stops.\n  break;\n  /// Trailing.\n}\nlet x := 1;\n/// At the end.";
+    let tokens = calru::lexer::lexer(source).expect("Failed to lex input");
+    let (asts, _) = Parser::new(tokens).parse_program().expect("Failed to parse program");
+
+    assert_eq!(asts.len(), 3);
+    assert!(asts.iter().all(|ast| ast.doc.is_none()));
+}
+
+#[test]
+fn test_parse_from_streaming_lexer() {
+    let source = "let x :int := 1;\nlet mut y := x + 2;\ny += 1;";
+    let streamed = Parser::from_lexer(Lexer::new(source)).parse_program().unwrap().0;
+    let collected = Parser::new(lexer(source).unwrap()).parse_program().unwrap().0;
+    assert_eq!(format!("{:?}", streamed), format!("{:?}", collected));
+
+    let mut parser = Parser::from_lexer(Lexer::new(source));
+    assert_eq!(parser.current_token.as_ref().unwrap().token_type, TokenType::Let);
+    parser.advance();
+    assert_eq!(parser.current_token.as_ref().unwrap().value, "x");
+
+    // Lexer errors are reported ahead of the parse errors they cause.
+    let err = Parser::from_lexer(Lexer::new("let x := 1 & 2;\nlet y := #;")).parse_program().unwrap_err();
+    assert_eq!(
+        err,
+        "Unexpected '&' at position Position { line: 1, column: 12 }. Did you mean '&&'?\nInvalid character '#' at position Position { line: 2, column: 10 }."
+    );
+}
+
+#[test]
+fn test_type_annotations_ignore_whitespace() {
+    let parse = |source: &str| Parser::new(lexer(source).unwrap()).parse_program().map(|(asts, _)| format!("{:?}", asts));
+
+    let glued = parse("let x :int := 1;\nlet l :[string] := [];\nlet m :{string:int} := {};").unwrap();
+    let spaced = parse("let x : int := 1;\nlet l : [ string ] := [];\nlet m : { string : int } := {};").unwrap();
+    assert_eq!(glued, spaced);
+
+    let (asts, _) = Parser::new(lexer("let grid : [[int]] := [[1, 2], [3]];\nlet int := 2;").unwrap()).parse_program().unwrap();
+    assert!(matches!(&asts[0].node, ASTNode::Let { symbol_type: SymbolType::List(inner), .. } if **inner == SymbolType::List(Box::new(SymbolType::Int))));
+    assert!(matches!(&asts[1].node, ASTNode::Let { variable, symbol_type: SymbolType::Int, .. } if variable == "int"));
+
+    let err = parse("let l :[char] := [];").unwrap_err();
+    assert_eq!(err, "Unknown type 'char' at position Position { line: 1, column: 1 }.");
+}
+
+#[test]
+fn test_type_annotations_require_colon() {
+    let parse = |source: &str| Parser::new(lexer(source).unwrap()).parse_program().map(|(asts, _)| asts.len());
+
+    for source in ["let x int := 1;", "struct P { x int }", "let (a int, b float) := (1, 2.0);"] {
+        let err = parse(source).unwrap_err();
+        assert!(err.starts_with("Expected ':' before type"), "{}: {}", source, err);
+    }
+    assert_eq!(parse("struct P { x: int }
+let (a: int, b) := (1, 2.0);"), Ok(2));
+}
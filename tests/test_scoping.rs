@@ -0,0 +1,78 @@
+mod common;
+
+use calru::symbol_table::SymbolValue;
+use common::{interpreter_for, run, value_of};
+
+#[test]
+fn test_loop_body_declarations() {
+    let source = "let mut total :int := 0;\nlet mut i :int := 0;\nloop {\n  let step := i * 2;\n  total := total + step;\n  i := i + 1;\n  if (i == 3) then\n    break;\n  end\n}";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "total"), SymbolValue::Int(6));
+    assert!(interpreter.symbol_table().lookup("step").is_none());
+
+    let err = run("loop {\n  let step := 1;\n  break;\n}\nlet after := step + 1;").err().unwrap();
+    assert_eq!(err, "Undefined variable: step");
+}
+
+#[test]
+fn test_shadowing_in_blocks() {
+    let source = "let x :int := 1;\nlet mut inner :string := \"\";\nloop {\n  let x :string := \"shadow\";\n  inner := x;\n  break;\n}\nlet after := x + 1;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "inner"), SymbolValue::String("shadow".to_string()));
+    assert_eq!(value_of(&interpreter, "after"), SymbolValue::Int(2));
+
+    let err = run("loop {\n  let x := 1;\n  let x := 2;\n  break;\n}").err().unwrap();
+    assert_eq!(err, "Variable 'x' already declared at position Position { line: 3, column: 7 }.");
+
+    let err = run("const limit := 10;\nloop {\n  let limit := 1;\n  break;\n}").err().unwrap();
+    assert_eq!(
+        err,
+        "Cannot shadow constant 'limit' at position Position { line: 3, column: 7 }; it is declared at position Position { line: 1, column: 7 }."
+    );
+
+    let err = run("let x := 1;\nlet (y, x) := (1, 2);").err().unwrap();
+    assert_eq!(err, "Variable 'x' already declared at position Position { line: 2, column: 9 }.");
+
+    let err = run("let (a, a) := (1, 2);").err().unwrap();
+    assert_eq!(err, "Variable 'a' already declared at position Position { line: 1, column: 9 }.");
+}
+
+#[test]
+fn test_if_branch_declarations() {
+    let source = "let mut picked :int := 0;\nif (false) then\n  let y := 1;\nelse\n  let y := 2;\nend\nif (true) then\n  picked := 3;\nend";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "picked"), SymbolValue::Int(3));
+    assert!(interpreter.symbol_table().lookup("y").is_none());
+
+    let err = run("if (true) then\n  let y := 1;\nend\nlet z := y + 1;").err().unwrap();
+    assert_eq!(err, "Undefined variable: y");
+}
+
+#[test]
+fn test_catch_and_match_bindings() {
+    let source = "let mut count :int := 0;\ntry {\n  throw \"first\";\n} catch (e) {\n  count := count + 1;\n}\ntry {\n  let e := 5;\n  throw \"second\";\n} catch (e) {\n  count := count + e.line;\n}";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "count"), SymbolValue::Int(10));
+    assert!(interpreter.symbol_table().lookup("e").is_none());
+
+    let source = "enum Shape {\n  Circle(int),\n  Empty,\n}\nlet r :int := 1;\nlet s := Shape.Circle(4);\nlet area := match s {\n  Shape.Circle(r) => r * r,\n  Shape.Empty => 0,\n};";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "area"), SymbolValue::Int(16));
+    assert_eq!(value_of(&interpreter, "r"), SymbolValue::Int(1));
+}
+
+#[test]
+fn test_block_constants_are_bound_at_run_time() {
+    let source = "let mut x := 2;\nconst N := 1;\nloop {\n  const M := N + 1;\n  x += M;\n  if (x > 5) then\n    break;\n  end\n}";
+    let interpreter = run(source).expect("program should run");
+    assert_eq!(value_of(&interpreter, "x"), SymbolValue::Int(6));
+
+    let (mut interpreter, asts) = interpreter_for("test \"t\" {\n  const N := 2;\n  assert_eq(N, 2);\n}").unwrap();
+    let outcomes = interpreter.run_tests(asts).expect("setup should run");
+    assert_eq!(outcomes[0].result, Ok(()));
+}
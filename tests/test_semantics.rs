@@ -1,12 +1,11 @@
 
 #[cfg(test)]
 mod tests {
-    use super::*;
     use calru::parser::Parser;
     use calru::models::{TokenType, Token, Position};
     use calru::ast::ASTNode;
-    use calru::symbol_table::SymbolTable;
     use calru::ast::AST;
+    use calru::symbol_table::{Mutability, SymbolType};
 
     fn create_tokens(tokens: Vec<(&str, TokenType)>) -> Vec<Token> {
         tokens.into_iter()
@@ -15,6 +14,7 @@ mod tests {
                 token_type,
                 value: value.to_string(),
                 position: Position { line: 1, column: i + 1 },
+                span: 0..0,
             })
             .collect()
     }
@@ -24,7 +24,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
@@ -32,8 +33,10 @@ mod tests {
 
         let mut parser = Parser::new(tokens);
 
-        let expected_ast = AST::new(ASTNode::Assignment {
+        let expected_ast = AST::new(ASTNode::Let {
             variable: "variable1".to_string(),
+            symbol_type: SymbolType::Int,
+            mutability: Mutability::Immutable,
             expression: Box::new(AST::new(ASTNode::Int(1))),
         });
 
@@ -48,25 +51,26 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             (";", TokenType::Termination),
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("2", TokenType::Number),
             (";", TokenType::Termination),
         ]);
 
         let mut parser = Parser::new(tokens);
-        let mut symbol_table = SymbolTable::new();
 
         parser.parse_statement().unwrap();
 
         let err = parser.parse_statement().err().unwrap();
-        assert_eq!(err, "Variable 'variable1' already declared at position Position { line: 1, column: 1 }.");
+        assert_eq!(err, "Variable 'variable1' already declared at position Position { line: 1, column: 9 }.");
     }
 
     #[test]
@@ -74,17 +78,17 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1.5", TokenType::FloatNumber),
             (";", TokenType::Termination),
         ]);
 
         let mut parser = Parser::new(tokens);
-        let mut symbol_table = SymbolTable::new();
 
         let err = parser.parse_statement().err().unwrap();
-        assert_eq!(err, "Type mismatch: cannot assign expression of type Float to variable of type Int at position Position { line: 1, column: 1 }.");
+        assert_eq!(err, "Type mismatch: cannot assign expression of type Float to variable of type Int at position Position { line: 1, column: 2 }.");
     }
 
     #[test]
@@ -92,7 +96,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
         ]);
@@ -108,7 +113,8 @@ mod tests {
         let tokens = create_tokens(vec![
             ("let", TokenType::Let),
             ("variable1", TokenType::Identifier),
-            (":int", TokenType::IntType),
+            (":", TokenType::Colon),
+            ("int", TokenType::Identifier),
             (":=", TokenType::Assign),
             ("1", TokenType::Number),
             ("+", TokenType::Operator),
@@ -118,8 +124,10 @@ mod tests {
 
         let mut parser = Parser::new(tokens);
 
-        let expected_ast = AST::new(ASTNode::Assignment {
+        let expected_ast = AST::new(ASTNode::Let {
             variable: "variable1".to_string(),
+            symbol_type: SymbolType::Int,
+            mutability: Mutability::Immutable,
             expression: Box::new(AST::new(ASTNode::BinaryOperation {
                 operator: "+".to_string(),
                 left: Box::new(AST::new(ASTNode::Int(1))),
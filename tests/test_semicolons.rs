@@ -0,0 +1,102 @@
+mod common;
+
+use calru::lexer::Lexer;
+use calru::models::TokenType;
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+const PROGRAM: &str = "let mut total := 0
+let steps := [
+  1,
+  2
+]
+struct Point {
+  x: int,
+  y: int,
+}
+let p := Point { x: 3, y: 4 }
+loop {
+  total += p.x + steps[1]
+  if (total > 9) then
+    break
+  end
+}
+try {
+  throw \"oops\"
+}
+catch (e) {
+  total += e.line
+}
+";
+
+#[test]
+fn test_semicolons_inserted_at_newlines() {
+    let interpreter = run(&format!("// semicolons: auto\n{}", PROGRAM)).expect("program should run");
+    assert_eq!(value_of(&interpreter, "total"), SymbolValue::Int(29));
+
+    // Explicit semicolons still work in auto mode.
+    let interpreter = run("// semicolons: auto\nlet x := 1; let y := x + 1\n").expect("program should run");
+    assert_eq!(value_of(&interpreter, "y"), SymbolValue::Int(2));
+
+    // Without the pragma, semicolons stay mandatory.
+    let err = run(PROGRAM).err().unwrap();
+    assert!(err.starts_with("Expected ';'"), "{}", err);
+}
+
+#[test]
+fn test_inserted_semicolon_tokens() {
+    let source = "stdout(a,\n  b)\nx := [1,\n 2]\nloop {\n  break\n}";
+    let tokens: Vec<_> = Lexer::new(source).with_semicolon_insertion(true).map(Result::unwrap).collect();
+    let inserted: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::Termination)
+        .map(|token| (token.text, token.position.line))
+        .collect();
+    // None inside parentheses or brackets, none after '{', and one at the end of the source.
+    assert_eq!(inserted, [("\n", 2), ("\n", 4), ("\n", 6), ("", 7)]);
+
+    let default: Vec<_> = Lexer::new(source).map(Result::unwrap).collect();
+    assert!(default.iter().all(|token| token.token_type != TokenType::Termination));
+}
+
+#[test]
+fn test_multi_line_braces_without_trailing_commas() {
+    let source = "// semicolons: auto
+struct Point {
+  x: int,
+  y: int
+}
+enum Shape {
+  Dot,
+  Square(int)
+}
+let m := {
+  \"a\": 1,
+  \"b\": 2
+}
+let p := Point {
+  x: 3,
+  y: m[\"b\"]
+}
+let s := Shape.Square(p.y)
+let side := match s {
+  Shape.Dot => 0,
+  Shape.Square(n) => n
+}
+test \"blocks still end statements\" {
+  assert_eq(side, 2)
+}
+";
+    let interpreter = run(source).expect("program should run");
+    assert_eq!(value_of(&interpreter, "side"), SymbolValue::Int(2));
+
+    // Braces of a block hold statements, so newlines inside them still end one.
+    let source = "let m := {\n  1: 2\n}\ntry {\n  throw \"x\"\n}";
+    let tokens: Vec<_> = Lexer::new(source).with_semicolon_insertion(true).map(Result::unwrap).collect();
+    let inserted: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.token_type == TokenType::Termination)
+        .map(|token| token.position.line)
+        .collect();
+    assert_eq!(inserted, [3, 5, 6]);
+}
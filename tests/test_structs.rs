@@ -0,0 +1,53 @@
+mod common;
+
+use calru::symbol_table::SymbolValue;
+use common::{run, value_of};
+
+#[test]
+fn test_struct_literal_and_field_access() {
+    let source = "struct Point { x: float, y: float }\nlet p :Point := Point { y: 2.0, x: 1.0 };\nlet sum :float := p.x + p.y;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(
+        value_of(&interpreter, "p"),
+        SymbolValue::Struct {
+            name: "Point".to_string(),
+            fields: vec![("x".to_string(), SymbolValue::Float(1.0)), ("y".to_string(), SymbolValue::Float(2.0))],
+        }
+    );
+    assert_eq!(value_of(&interpreter, "sum"), SymbolValue::Float(3.0));
+}
+
+#[test]
+fn test_field_assignment_and_nested_structs() {
+    let source = "struct Point { x: float, y: float }\nstruct Line { from: Point, to: Point }\nlet mut l :Line := Line { from: Point { x: 0.0, y: 0.0 }, to: Point { x: 1.0, y: 1.0 } };\nl.to.x := 3.0;\nlet x :float := l.to.x;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "x"), SymbolValue::Float(3.0));
+}
+
+#[test]
+fn test_struct_field_errors() {
+    let decl = "struct Point { x: float, y: float }\n";
+
+    let err = run(&format!("{}let p :Point := Point {{ x: 1.0 }};", decl)).err().unwrap();
+    assert!(err.starts_with("Missing field(s) y in 'Point' literal"));
+
+    let err = run(&format!("{}let p :Point := Point {{ x: 1.0, y: 2.0, z: 3.0 }};", decl)).err().unwrap();
+    assert!(err.starts_with("Struct 'Point' has no field 'z'"));
+
+    let err = run(&format!("{}let p :Point := Point {{ x: 1, y: 2.0 }};", decl)).err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Int to field 'x' of type Float"));
+
+    let err = run(&format!("{}let mut p :Point := Point {{ x: 1.0, y: 2.0 }};\np.x := true;", decl)).err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign expression of type Boolean to field 'x' of type Float"));
+
+    let err = run(&format!("{}let p :Point := Point {{ x: 1.0, y: 2.0 }};\nlet z :float := p.z;", decl)).err().unwrap();
+    assert_eq!(err, "Struct 'Point' has no field 'z'.");
+
+    let err = run("struct Point { x: float, x: float }").err().unwrap();
+    assert_eq!(err, "Field 'x' already declared in struct 'Point' at position Position { line: 1, column: 26 }.");
+
+    let err = run("struct Point { x: float }\nenum Point { A }").err().unwrap();
+    assert_eq!(err, "Type 'Point' already declared at position Position { line: 2, column: 6 }.");
+}
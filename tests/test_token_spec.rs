@@ -0,0 +1,78 @@
+use calru::lexer::lexer;
+use calru::models::TokenType;
+use calru::token_spec::{highlighting_grammar, highlighting_rules, KEYWORDS, SYMBOLS};
+use regex::Regex;
+
+#[test]
+fn test_every_table_entry_lexes() {
+    for (keyword, token_type, _) in KEYWORDS {
+        let tokens = lexer(keyword).unwrap();
+        assert_eq!(tokens[0].token_type, *token_type, "keyword {}", keyword);
+        assert_eq!(tokens[1].token_type, TokenType::EOF);
+    }
+    for (symbol, token_type, _) in SYMBOLS {
+        let tokens = lexer(&format!("{} x", symbol)).unwrap();
+        assert_eq!(tokens[0].token_type, *token_type, "symbol {}", symbol);
+        assert_eq!(tokens[0].value, *symbol);
+    }
+
+    // The longest symbol wins.
+    let types: Vec<TokenType> = lexer("a<=b:=c").unwrap().iter().map(|t| t.token_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::Identifier,
+            TokenType::LessThanOrEqual,
+            TokenType::Identifier,
+            TokenType::Assign,
+            TokenType::Identifier,
+            TokenType::EOF,
+        ]
+    );
+}
+
+#[test]
+fn test_highlighting_grammar_export() {
+    let grammar = highlighting_grammar();
+
+    assert!(grammar.contains("\"scopeName\": \"source.calru\""));
+    assert!(grammar.contains(r#"{ "name": "keyword.control.calru", "match": "\\b(?:if|then|else|end|loop|break|match|try|catch|throw|test)\\b" }"#));
+    assert!(grammar.contains(r#"{ "name": "keyword.operator.assignment.calru", "match": ":=|\\+=|\\-=|\\*=|/=|%=" }"#));
+    assert!(grammar.contains(r#""begin": "/\\*", "end": "\\*/""#));
+    assert!(grammar.contains(r#"{ "name": "variable.other.calru", "match": "[_\\p{XID_Start}]\\p{XID_Continue}*" }"#));
+}
+
+/// Highlights `line` the way an editor applies the exported rules: at each step the match
+/// starting first wins, the earlier rule on a tie.
+fn highlight(line: &str) -> Vec<(&str, &'static str)> {
+    let rules: Vec<(&'static str, Regex)> =
+        highlighting_rules().into_iter().map(|(scope, pattern)| (scope, Regex::new(&pattern).unwrap())).collect();
+    let mut spans = Vec::new();
+    let mut at = 0;
+    while let Some((scope, found)) = rules
+        .iter()
+        .filter_map(|(scope, regex)| regex.find_at(line, at).map(|found| (*scope, found)))
+        .min_by_key(|(_, found)| found.start())
+    {
+        spans.push((found.as_str(), scope));
+        at = found.end();
+    }
+    spans
+}
+
+#[test]
+fn test_highlighting_identifiers_with_digits() {
+    assert_eq!(
+        highlight("let variable1 :int := x2 + 10;"),
+        vec![
+            ("let", "storage.type.calru"),
+            ("variable1", "variable.other.calru"),
+            ("int", "storage.type.primitive.calru"),
+            (":=", "keyword.operator.assignment.calru"),
+            ("x2", "variable.other.calru"),
+            ("+", "keyword.operator.calru"),
+            ("10", "constant.numeric.integer.calru"),
+            (";", "punctuation.terminator.calru"),
+        ]
+    );
+}
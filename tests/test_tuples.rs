@@ -0,0 +1,47 @@
+mod common;
+
+use calru::symbol_table::{SymbolType, SymbolValue};
+use common::{run, value_of};
+
+#[test]
+fn test_tuple_literal_and_indexing() {
+    let source = "let t :(int, float) := (1, 2.5);\nlet a :int := t.0;\nlet b :float := t.1;";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(
+        interpreter.symbol_table().lookup("t").unwrap().symbol_type,
+        SymbolType::Tuple(vec![SymbolType::Int, SymbolType::Float])
+    );
+    assert_eq!(value_of(&interpreter, "a"), SymbolValue::Int(1));
+    assert_eq!(value_of(&interpreter, "b"), SymbolValue::Float(2.5));
+}
+
+#[test]
+fn test_nested_tuple_indexing() {
+    let interpreter = run("let t :(int, (bool, string)) := (1, (true, \"x\"));\nlet s :string := t.1.1;").expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "s"), SymbolValue::String("x".to_string()));
+}
+
+#[test]
+fn test_destructuring_let() {
+    let source = "let t :(int, float) := (3, 0.5);\nlet (a, b :float) := t;\nlet (c, d) := (a + 1, \"d\");";
+    let interpreter = run(source).expect("program should run");
+
+    assert_eq!(value_of(&interpreter, "a"), SymbolValue::Int(3));
+    assert_eq!(value_of(&interpreter, "b"), SymbolValue::Float(0.5));
+    assert_eq!(value_of(&interpreter, "c"), SymbolValue::Int(4));
+    assert_eq!(value_of(&interpreter, "d"), SymbolValue::String("d".to_string()));
+}
+
+#[test]
+fn test_destructuring_type_errors() {
+    let err = run("let (a :int, b :int) := (1, 2.5);").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot assign element of type Float to variable 'b' of type Int"));
+
+    let err = run("let (a, b, c) := (1, 2);").err().unwrap();
+    assert!(err.starts_with("Type mismatch: cannot destructure a tuple of 2 elements into 3 variables"));
+
+    let err = run("let t :(int, int) := (1, 2);\nlet x :int := t.2;").err().unwrap();
+    assert_eq!(err, "Tuple index 2 out of bounds for a tuple of 2 elements.");
+}